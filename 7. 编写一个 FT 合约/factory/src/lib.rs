@@ -0,0 +1,408 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LazyOption, UnorderedMap};
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::{
+    env, is_promise_success, log, near_bindgen, require, serde_json, AccountId, Balance,
+    BorshStorageKey, CryptoHash, Gas, PanicOnDefault, Promise,
+};
+
+const GAS_FOR_INIT: Gas = Gas(30_000_000_000_000);
+const GAS_FOR_RESOLVE_CREATE_TOKEN: Gas = Gas(10_000_000_000_000);
+// 部署代码本身消耗的 gas 与代码大小有关, 额外预留 100 TGas
+const GAS_FOR_CREATE_TOKEN: Gas =
+    Gas(100_000_000_000_000 + GAS_FOR_INIT.0 + GAS_FOR_RESOLVE_CREATE_TOKEN.0);
+
+// 除代码和元数据外, hello_ft 初始化后占用的存储: 账户本身的 100 字节, 以及 64 个字符的所有者初始化并 mint 后
+// 写入的状态, 后者由 hello_ft 的 `test_init_storage_usage` 测量, 不超过 3000 字节
+const TOKEN_STATE_BYTES: u64 = 3_100;
+
+// 每次最多查询的 FT 数, 也是不指定 `limit` 时的默认值
+const MAX_TOKENS_LIMIT: u64 = 100;
+
+// hello_ft 工厂合约: 保存 hello_ft 的合约代码, 为每个 FT 创建子账户 `<prefix>.<factory>` 并部署初始化
+// 创建者需要支付新账户的存储费, 创建失败时退回
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    // 合约所有者能更新 hello_ft 的合约代码
+    owner_id: AccountId,
+    // hello_ft 的合约代码, 使用 `LazyOption` 避免每次调用都读取
+    code: LazyOption<Vec<u8>>,
+    // 已创建的 FT 合约及其所有者, 创建中的 FT 也在其中, 创建失败后移除
+    tokens: UnorderedMap<AccountId, AccountId>,
+}
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    Code,
+    Tokens,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Token {
+    pub token_id: AccountId,
+    pub owner_id: AccountId,
+}
+
+// hello_ft `init` 的参数
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct InitArgs {
+    owner_id: AccountId,
+    metadata: FungibleTokenMetadata,
+    max_supply: Option<U128>,
+    initial_supply: Option<U128>,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn init(owner_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            code: LazyOption::new(StorageKey::Code, None),
+            tokens: UnorderedMap::new(StorageKey::Tokens),
+        }
+    }
+
+    // 合约所有者能更新 hello_ft 的合约代码, 只影响之后创建的 FT
+    pub fn set_code(&mut self, code: Base64VecU8) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only contract owner can call this method."
+        );
+        self.code.set(&code.0);
+    }
+
+    // 查询 hello_ft 合约代码的哈希, 未设置代码时返回 `None`
+    pub fn code_hash(&self) -> Option<Base58CryptoHash> {
+        self.code.get().map(|code| {
+            let code_hash: CryptoHash = env::sha256(&code).try_into().unwrap();
+            code_hash.into()
+        })
+    }
+
+    // 查询使用 `metadata` 创建 FT 时新账户需要的存储费, 创建时还需要额外支付工厂合约记录该 FT 的存储费
+    pub fn create_token_deposit(&self, metadata: FungibleTokenMetadata) -> U128 {
+        U128(self.internal_token_storage_cost(&metadata))
+    }
+
+    // 创建 FT 合约 `<prefix>.<factory>`, `initial_supply` 会 mint 给 `owner_id`
+    // 附加的 NEAR 扣除索引的存储费后全部转给新账户, 创建失败时全部退回
+    #[payable]
+    pub fn create_token(
+        &mut self,
+        prefix: String,
+        owner_id: AccountId,
+        metadata: FungibleTokenMetadata,
+        max_supply: Option<U128>,
+        initial_supply: U128,
+    ) -> Promise {
+        require!(
+            env::prepaid_gas() >= GAS_FOR_CREATE_TOKEN,
+            "More gas is required"
+        );
+        let code = self
+            .code
+            .get()
+            .unwrap_or_else(|| env::panic_str("Token code is not set."));
+        require!(!prefix.contains('.'), "Token prefix must not contain '.'.");
+        let token_id: AccountId = format!("{}.{}", prefix, env::current_account_id())
+            .parse()
+            .unwrap_or_else(|_| env::panic_str("Invalid token prefix."));
+        require!(
+            self.tokens.get(&token_id).is_none(),
+            "Token already exists."
+        );
+
+        // 先记录 FT, 避免创建过程中重复创建同名 FT, 索引的存储费由创建者支付
+        let storage_usage = env::storage_usage();
+        self.tokens.insert(&token_id, &owner_id);
+        let index_cost =
+            Balance::from(env::storage_usage() - storage_usage) * env::storage_byte_cost();
+
+        let amount = env::attached_deposit();
+        require!(
+            amount >= self.internal_token_storage_cost(&metadata) + index_cost,
+            "Not enough attached deposit to create the token."
+        );
+        let args = InitArgs {
+            owner_id,
+            metadata,
+            max_supply,
+            initial_supply: Some(initial_supply),
+        };
+
+        Promise::new(token_id.clone())
+            .create_account()
+            .transfer(amount - index_cost)
+            .deploy_contract(code)
+            .function_call(
+                "init".to_string(),
+                serde_json::to_vec(&args).unwrap(),
+                0,
+                GAS_FOR_INIT,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_CREATE_TOKEN)
+                    .resolve_create_token(token_id, env::predecessor_account_id(), U128(amount)),
+            )
+    }
+
+    // 分页查询已创建的 FT
+    pub fn tokens(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<Token> {
+        let token_ids = self.tokens.keys_as_vector();
+        let owner_ids = self.tokens.values_as_vector();
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(MAX_TOKENS_LIMIT, |limit| limit.0.min(MAX_TOKENS_LIMIT));
+        let end = from_index.saturating_add(limit).min(token_ids.len());
+        (from_index..end)
+            .map(|index| Token {
+                token_id: token_ids.get(index).unwrap(),
+                owner_id: owner_ids.get(index).unwrap(),
+            })
+            .collect()
+    }
+
+    pub fn token_count(&self) -> U64 {
+        U64(self.tokens.len())
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // `create_token` 的回调, 创建失败时移除索引并退回创建者附加的 NEAR
+    // 创建失败时 batch 中转给新账户的 NEAR 会退回给工厂合约
+    #[private]
+    pub fn resolve_create_token(
+        &mut self,
+        token_id: AccountId,
+        payer_id: AccountId,
+        amount: U128,
+    ) -> bool {
+        if is_promise_success() {
+            log!("Token {} is successfully created.", token_id);
+            true
+        } else {
+            log!("Failed to create token {}, refund the money.", token_id);
+            self.tokens.remove(&token_id);
+            Promise::new(payer_id).transfer(amount.0);
+            false
+        }
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 元数据按 JSON 的长度估算, 不小于其在 hello_ft 中 borsh 序列化后的长度
+    fn internal_token_storage_cost(&self, metadata: &FungibleTokenMetadata) -> Balance {
+        let code_len = self.code.get().map_or(0, |code| code.len() as u64);
+        let metadata_len = serde_json::to_vec(metadata).unwrap().len() as u64;
+        Balance::from(code_len + TOKEN_STATE_BYTES + metadata_len) * env::storage_byte_cost()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Contract, Token, MAX_TOKENS_LIMIT};
+    use near_contract_standards::fungible_token::metadata::{
+        FungibleTokenMetadata, FT_METADATA_SPEC,
+    };
+    use near_sdk::json_types::{Base64VecU8, U128, U64};
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::{
+        env, serde_json, testing_env, AccountId, CryptoHash, Gas, PromiseResult, RuntimeFeesConfig,
+        VMConfig, ONE_NEAR,
+    };
+
+    const CODE: &[u8] = b"\0asm hello_ft";
+
+    fn factory() -> AccountId {
+        "factory.near".parse().unwrap()
+    }
+
+    fn owner() -> AccountId {
+        "owner.near".parse().unwrap()
+    }
+
+    fn alice() -> AccountId {
+        "alice.near".parse().unwrap()
+    }
+
+    fn token() -> AccountId {
+        "hello.factory.near".parse().unwrap()
+    }
+
+    fn metadata() -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "Hello Fungible Token".to_string(),
+            symbol: "HelloFT".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: 18,
+        }
+    }
+
+    fn setup() -> Contract {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(factory())
+            .predecessor_account_id(owner())
+            .build());
+
+        let mut contract = Contract::init(owner());
+        contract.set_code(Base64VecU8(CODE.to_vec()));
+        contract
+    }
+
+    // Alice 附加 `deposit` 创建 hello.factory.near
+    fn create_token(contract: &mut Contract, prefix: &str, deposit: u128) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(factory())
+            .predecessor_account_id(alice())
+            .attached_deposit(deposit)
+            .account_balance(100 * ONE_NEAR)
+            .prepaid_gas(Gas(300_000_000_000_000))
+            .build());
+
+        contract.create_token(
+            prefix.to_string(),
+            alice(),
+            metadata(),
+            Some(U128(10_000)),
+            U128(1000),
+        );
+    }
+
+    fn resolve_create_token(contract: &mut Contract, result: PromiseResult) -> bool {
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(factory())
+                .predecessor_account_id(factory())
+                .account_balance(100 * ONE_NEAR)
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+
+        contract.resolve_create_token(token(), alice(), U128(ONE_NEAR))
+    }
+
+    #[test]
+    fn test_create_token() {
+        let mut contract = setup();
+
+        create_token(&mut contract, "hello", ONE_NEAR);
+
+        // 创建账户, 转账, 部署和初始化在同一个 receipt 中
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, token());
+        match &receipts[0].actions[..] {
+            [VmAction::CreateAccount, VmAction::Transfer { .. }, VmAction::DeployContract { code }, VmAction::FunctionCall {
+                function_name,
+                args,
+                ..
+            }] => {
+                assert_eq!(code, CODE);
+                assert_eq!(function_name, "init");
+                let args: serde_json::Value = serde_json::from_slice(args).unwrap();
+                assert_eq!(args["owner_id"], "alice.near");
+                assert_eq!(args["max_supply"], "10000");
+                assert_eq!(args["initial_supply"], "1000");
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(resolve_create_token(
+            &mut contract,
+            PromiseResult::Successful(Vec::new())
+        ));
+        assert_eq!(contract.token_count(), U64(1));
+        assert_eq!(
+            contract.tokens(None, None),
+            vec![Token {
+                token_id: token(),
+                owner_id: alice(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tokens_limit() {
+        let mut contract = setup();
+
+        for index in 0..MAX_TOKENS_LIMIT + 1 {
+            let token_id = format!("token{}.{}", index, factory()).parse().unwrap();
+            contract.tokens.insert(&token_id, &alice());
+        }
+
+        assert_eq!(contract.tokens(None, None).len() as u64, MAX_TOKENS_LIMIT);
+        assert_eq!(
+            contract.tokens(None, Some(U64(u64::MAX))).len() as u64,
+            MAX_TOKENS_LIMIT
+        );
+        assert_eq!(contract.tokens(Some(U64(MAX_TOKENS_LIMIT)), None).len(), 1);
+    }
+
+    #[test]
+    fn test_create_token_failed() {
+        let mut contract = setup();
+
+        create_token(&mut contract, "hello", ONE_NEAR);
+
+        assert!(!resolve_create_token(&mut contract, PromiseResult::Failed));
+        assert_eq!(contract.token_count(), U64(0));
+
+        // 退回创建者附加的 NEAR
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, alice());
+        assert!(matches!(
+            &receipts[0].actions[..],
+            [VmAction::Transfer { deposit }] if *deposit == ONE_NEAR
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token already exists.")]
+    fn test_create_existing_token() {
+        let mut contract = setup();
+
+        create_token(&mut contract, "hello", ONE_NEAR);
+        create_token(&mut contract, "hello", ONE_NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token prefix must not contain '.'.")]
+    fn test_create_token_with_nested_prefix() {
+        let mut contract = setup();
+
+        create_token(&mut contract, "a.hello", ONE_NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough attached deposit to create the token.")]
+    fn test_create_token_without_enough_deposit() {
+        let mut contract = setup();
+
+        let deposit = contract.create_token_deposit(metadata()).0;
+        create_token(&mut contract, "hello", deposit);
+    }
+
+    #[test]
+    fn test_code_hash() {
+        let contract = setup();
+
+        let code_hash: CryptoHash = env::sha256(CODE).try_into().unwrap();
+
+        assert_eq!(contract.code_hash(), Some(code_hash.into()));
+    }
+}
@@ -0,0 +1,180 @@
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    env, log, near_bindgen, require, AccountId, Balance, BorshStorageKey, PanicOnDefault,
+    PromiseOrValue,
+};
+
+// hello_ft 接收合约的参考实现: 一个简单的存款金库
+// 用户通过 `ft_transfer_call` 存入 FT, `msg` 决定金库如何处理这笔转账
+// * `""`: 全部存入
+// * `"refund:<amount>"`: 退回 `amount`, 剩余部分存入
+// * `"refund_all"`: 全部退回
+// * 其他: 拒绝这笔转账, 调用失败后 FT 合约会全部退回
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    // 只接收该 FT 合约的转账
+    token_id: AccountId,
+    // 用户存入的 FT 数量
+    deposits: LookupMap<AccountId, Balance>,
+}
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    Deposits,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn init(token_id: AccountId) -> Self {
+        Self {
+            token_id,
+            deposits: LookupMap::new(StorageKey::Deposits),
+        }
+    }
+
+    pub fn token_id(&self) -> AccountId {
+        self.token_id.clone()
+    }
+
+    pub fn deposit_of(&self, account_id: AccountId) -> U128 {
+        U128(self.deposits.get(&account_id).unwrap_or(0))
+    }
+}
+
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+    // 返回值为退回给 `sender_id` 的 FT 数量
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        require!(
+            env::predecessor_account_id() == self.token_id,
+            "Only accepts transfers from the token contract."
+        );
+
+        let refund = match msg.as_str() {
+            "" => 0,
+            "refund_all" => amount.0,
+            _ => {
+                let refund = msg
+                    .strip_prefix("refund:")
+                    .and_then(|refund| refund.parse::<Balance>().ok());
+                require!(refund.is_some(), "Unknown msg.");
+                refund.unwrap()
+            }
+        };
+        require!(
+            refund <= amount.0,
+            "Can not refund more than the received amount."
+        );
+
+        let deposit = amount.0 - refund;
+        if deposit > 0 {
+            let balance = self.deposits.get(&sender_id).unwrap_or(0);
+            self.deposits.insert(&sender_id, &(balance + deposit));
+        }
+        log!("{} deposited {}, refund {}", sender_id, deposit, refund);
+
+        PromiseOrValue::Value(U128(refund))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Contract;
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, AccountId, PromiseOrValue};
+
+    fn token() -> AccountId {
+        "hello_ft.near".parse().unwrap()
+    }
+
+    fn alice() -> AccountId {
+        "alice.near".parse().unwrap()
+    }
+
+    fn setup() -> Contract {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(token())
+            .build());
+
+        Contract::init(token())
+    }
+
+    fn refund_of(result: PromiseOrValue<U128>) -> U128 {
+        match result {
+            PromiseOrValue::Value(refund) => refund,
+            PromiseOrValue::Promise(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_deposit() {
+        let mut contract = setup();
+
+        let refund = refund_of(contract.ft_on_transfer(alice(), U128(100), String::new()));
+
+        assert_eq!(refund, U128(0));
+        assert_eq!(contract.deposit_of(alice()), U128(100));
+    }
+
+    #[test]
+    fn test_partial_refund() {
+        let mut contract = setup();
+
+        let refund =
+            refund_of(contract.ft_on_transfer(alice(), U128(100), "refund:30".to_string()));
+
+        assert_eq!(refund, U128(30));
+        assert_eq!(contract.deposit_of(alice()), U128(70));
+    }
+
+    #[test]
+    fn test_refund_all() {
+        let mut contract = setup();
+
+        let refund =
+            refund_of(contract.ft_on_transfer(alice(), U128(100), "refund_all".to_string()));
+
+        assert_eq!(refund, U128(100));
+        assert_eq!(contract.deposit_of(alice()), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown msg.")]
+    fn test_unknown_msg() {
+        let mut contract = setup();
+
+        contract.ft_on_transfer(alice(), U128(100), "panic".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Can not refund more than the received amount.")]
+    fn test_refund_more_than_received() {
+        let mut contract = setup();
+
+        contract.ft_on_transfer(alice(), U128(100), "refund:101".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only accepts transfers from the token contract.")]
+    fn test_transfer_from_other_token() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .build());
+
+        contract.ft_on_transfer(alice(), U128(100), String::new());
+    }
+}
@@ -0,0 +1,344 @@
+use crate::events::Approval;
+use crate::{Contract, ContractExt};
+use near_sdk::json_types::U128;
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, require, AccountId, Balance, Promise, StorageUsage,
+};
+
+// 类似 ERC20 的授权转账 (拉取模式)
+// 授权记录的存储费由新建记录的调用者附加的 NEAR 支付, 通过 `ft_permit` 提交时为 relayer,
+// 授权记录被移除时退还给当初的支付者
+#[near_bindgen]
+impl Contract {
+    pub fn ft_allowance(&self, owner_id: AccountId, spender_id: AccountId) -> U128 {
+        U128(self.internal_allowance(&owner_id, &spender_id))
+    }
+
+    // 设置 `spender_id` 能使用的授权额度, 会覆盖之前的额度. 新建授权记录需要附加 NEAR 支付存储费
+    #[payable]
+    pub fn ft_approve(&mut self, spender_id: AccountId, amount: U128) {
+        let owner_id = env::predecessor_account_id();
+        self.internal_approve(&owner_id, &spender_id, amount.0);
+    }
+
+    // 在原有授权额度上增加额度
+    #[payable]
+    pub fn ft_increase_allowance(&mut self, spender_id: AccountId, amount: U128) {
+        let owner_id = env::predecessor_account_id();
+        let allowance = self
+            .internal_allowance(&owner_id, &spender_id)
+            .checked_add(amount.0);
+        require!(allowance.is_some(), "Allowance overflow.");
+        self.internal_approve(&owner_id, &spender_id, allowance.unwrap());
+    }
+
+    // 在原有授权额度上减少额度, 减少到 0 时退还存储费
+    #[payable]
+    pub fn ft_decrease_allowance(&mut self, spender_id: AccountId, amount: U128) {
+        let owner_id = env::predecessor_account_id();
+        let allowance = self.internal_allowance(&owner_id, &spender_id);
+        require!(
+            allowance >= amount.0,
+            "Allowance can not be decreased below zero."
+        );
+        self.internal_approve(&owner_id, &spender_id, allowance - amount.0);
+    }
+
+    // 被授权的用户消耗授权额度把 `owner_id` 的 FT 转给 `receiver_id`. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn ft_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let spender_id = env::predecessor_account_id();
+        self.assert_not_frozen(&spender_id);
+        self.assert_compliant(&spender_id);
+        self.internal_spend_allowance(&owner_id, &spender_id, amount.0);
+        self.internal_transfer(&owner_id, &receiver_id, amount.0, memo);
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 查询 `owner_id` 授权给 `spender_id` 的额度
+    pub(crate) fn internal_allowance(
+        &self,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+    ) -> Balance {
+        self.allowances
+            .get(&(owner_id.clone(), spender_id.clone()))
+            .unwrap_or(0)
+    }
+
+    // 设置授权额度并结算存储费
+    pub(crate) fn internal_approve(
+        &mut self,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        amount: Balance,
+    ) {
+        require!(
+            env::attached_deposit() >= 1,
+            "Requires attached deposit of at least 1 yoctoNEAR"
+        );
+        require!(
+            owner_id != spender_id,
+            "Owner and spender should be different"
+        );
+
+        // 移除授权记录释放的存储费退还给新建记录时的支付者
+        let payer_id = self.internal_allowance_payer(owner_id, spender_id);
+        let initial_storage_usage = env::storage_usage();
+        self.internal_set_allowance(owner_id, spender_id, amount);
+        settle_storage_deposit(initial_storage_usage, &payer_id);
+
+        // 打印事件 log
+        Approval {
+            owner_id,
+            spender_id,
+            amount: &U128(amount),
+        }
+        .emit();
+    }
+
+    // 授权记录的存储费支付者. 记录支付者之前新建的授权记录由授权者支付
+    fn internal_allowance_payer(&self, owner_id: &AccountId, spender_id: &AccountId) -> AccountId {
+        self.allowance_payers
+            .get(&(owner_id.clone(), spender_id.clone()))
+            .unwrap_or_else(|| owner_id.clone())
+    }
+
+    // 设置授权额度, 额度为 0 时移除记录. 新建记录时调用者为存储费支付者
+    pub(crate) fn internal_set_allowance(
+        &mut self,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        amount: Balance,
+    ) {
+        let key = (owner_id.clone(), spender_id.clone());
+        if amount == 0 {
+            self.allowances.remove(&key);
+            self.allowance_payers.remove(&key);
+        } else if self.allowances.insert(&key, &amount).is_none() {
+            self.allowance_payers
+                .insert(&key, &env::predecessor_account_id());
+        }
+    }
+
+    // 消耗 `spender_id` 在 `owner_id` 处的授权额度
+    // 额度耗尽时保留记录, 存储费只在授权者主动取消授权时退还
+    pub(crate) fn internal_spend_allowance(
+        &mut self,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        amount: Balance,
+    ) {
+        require!(
+            owner_id != spender_id,
+            "Owner and spender should be different"
+        );
+        let allowance = self.internal_allowance(owner_id, spender_id);
+        require!(allowance >= amount, "Insufficient allowance.");
+        self.allowances.insert(
+            &(owner_id.clone(), spender_id.clone()),
+            &(allowance - amount),
+        );
+    }
+}
+
+// 结算存储费, 新增的存储由调用者附加的 NEAR 支付, 多余的 NEAR 退还给调用者, 释放的存储退还给 `payer_id`
+pub(crate) fn settle_storage_deposit(initial_storage_usage: StorageUsage, payer_id: &AccountId) {
+    let predecessor_id = env::predecessor_account_id();
+    let attached_deposit = env::attached_deposit();
+    let storage_usage = env::storage_usage();
+
+    let (refund, released) = if storage_usage > initial_storage_usage {
+        let storage_cost =
+            Balance::from(storage_usage - initial_storage_usage) * env::storage_byte_cost();
+        require!(
+            attached_deposit >= storage_cost,
+            format!("Must attach {} yoctoNEAR to cover storage.", storage_cost)
+        );
+        (attached_deposit - storage_cost, 0)
+    } else {
+        let released =
+            Balance::from(initial_storage_usage - storage_usage) * env::storage_byte_cost();
+        (attached_deposit, released)
+    };
+
+    if *payer_id == predecessor_id {
+        refund_deposit(&predecessor_id, refund + released);
+    } else {
+        refund_deposit(&predecessor_id, refund);
+        refund_deposit(payer_id, released);
+    }
+}
+
+fn refund_deposit(account_id: &AccountId, amount: Balance) {
+    if amount > 0 {
+        Promise::new(account_id.clone()).transfer(amount);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, ONE_NEAR, ONE_YOCTO};
+
+    fn carol() -> AccountId {
+        "carol.near".parse().unwrap()
+    }
+
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(carol()), None);
+        contract
+    }
+
+    #[test]
+    fn test_approve_transfer_from() {
+        let mut contract = setup();
+
+        // ------------------------------ Bob 授权 Alice 100 FT ------------------------------------
+
+        // 附加 0.01 NEAR 支付授权记录的存储费, 多余部分会被退还
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+
+        contract.ft_approve(alice(), U128(100 * ONE_TOKEN));
+
+        assert_eq!(contract.ft_allowance(bob(), alice()), U128(100 * ONE_TOKEN));
+        assert!(get_logs()[0].contains(r#""event":"approval""#));
+
+        // ------------------------ Alice 通过授权把 Bob 的 60 FT 转给 Carol --------------------------
+
+        // 转账新增的存储由 Alice 支付
+        deposit_storage(&mut contract, alice());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_from(bob(), carol(), U128(60 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(940 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(carol()), U128(60 * ONE_TOKEN));
+        assert_eq!(contract.ft_allowance(bob(), alice()), U128(40 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_increase_decrease_allowance() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+
+        contract.ft_increase_allowance(alice(), U128(100 * ONE_TOKEN));
+        contract.ft_increase_allowance(alice(), U128(50 * ONE_TOKEN));
+
+        assert_eq!(contract.ft_allowance(bob(), alice()), U128(150 * ONE_TOKEN));
+
+        contract.ft_decrease_allowance(alice(), U128(30 * ONE_TOKEN));
+
+        assert_eq!(contract.ft_allowance(bob(), alice()), U128(120 * ONE_TOKEN));
+
+        contract.ft_decrease_allowance(alice(), U128(120 * ONE_TOKEN));
+
+        assert_eq!(contract.ft_allowance(bob(), alice()), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Allowance can not be decreased below zero.")]
+    fn test_decrease_allowance_below_zero() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+
+        contract.ft_approve(alice(), U128(100 * ONE_TOKEN));
+        contract.ft_decrease_allowance(alice(), U128(101 * ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient allowance.")]
+    fn test_transfer_from_over_allowance() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+
+        contract.ft_approve(alice(), U128(100 * ONE_TOKEN));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_from(bob(), carol(), U128(101 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient allowance.")]
+    fn test_transfer_from_without_approval() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_from(bob(), carol(), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "yoctoNEAR to cover storage.")]
+    fn test_approve_without_storage_deposit() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_approve(alice(), U128(100 * ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of at least 1 yoctoNEAR")]
+    fn test_approve_without_deposit() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .build());
+
+        contract.ft_approve(alice(), U128(100 * ONE_TOKEN));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::roles::Role;
+use crate::{Contract, ContractExt};
+use near_contract_standards::fungible_token::events::{FtMint, FtTransfer};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, require, AccountId, Balance, Gas};
+
+// 批量操作中每一项预留的 gas
+const GAS_PER_BATCH_ENTRY: Gas = Gas(5_000_000_000_000);
+
+// 批量 mint 和批量转账, 用于空投等场景
+#[near_bindgen]
+impl Contract {
+    // minter 能在一次调用中为多个用户 mint FT, 未注册的账户会被自动注册
+    pub fn mint_batch(&mut self, entries: Vec<(AccountId, U128)>, memo: Option<String>) {
+        self.assert_role(Role::Minter);
+        self.assert_not_wrapped_near();
+        assert_batch_gas(entries.len());
+
+        // 按 mint 总量消耗 mint 额度
+        let mut total_amount: Balance = 0;
+        for (_, amount) in entries.iter() {
+            require!(amount.0 > 0, "The amount should be a positive number");
+            total_amount = total_amount
+                .checked_add(amount.0)
+                .unwrap_or_else(|| env::panic_str("Total amount overflow."));
+        }
+        self.internal_use_mint_quota(&env::predecessor_account_id(), total_amount);
+
+        for (account_id, amount) in entries.iter() {
+            self.internal_mint_without_event(account_id, amount.0);
+        }
+
+        // 打印一条包含所有 mint 记录的标准 log
+        let events: Vec<FtMint> = entries
+            .iter()
+            .map(|(account_id, amount)| FtMint {
+                owner_id: account_id,
+                amount,
+                memo: memo.as_deref(),
+            })
+            .collect();
+        FtMint::emit_many(&events);
+    }
+
+    // 用户在一次调用中给多个用户转账. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn ft_transfer_batch(&mut self, entries: Vec<(AccountId, U128)>, memo: Option<String>) {
+        assert_one_yocto();
+        assert_batch_gas(entries.len());
+        let sender_id = env::predecessor_account_id();
+
+        // 检查暂停, 冻结, 合规和锁仓状态, 锁仓按转账总额检查
+        let mut total_amount: Balance = 0;
+        for (receiver_id, amount) in entries.iter() {
+            self.assert_transferable(&sender_id, receiver_id);
+            total_amount = total_amount
+                .checked_add(amount.0)
+                .unwrap_or_else(|| env::panic_str("Total amount overflow."));
+        }
+        self.assert_unlocked(&sender_id, total_amount);
+
+        // 接收者未注册时尝试由合约代付存储费
+        for (receiver_id, _) in entries.iter() {
+            self.internal_sponsor_registration(&sender_id, receiver_id);
+        }
+
+        // 每笔转账分别扣除手续费, 最后一次性转入金库. 转账新增的存储由发送者支付
+        let initial_storage_usage = env::storage_usage();
+        let mut total_fee: Balance = 0;
+        let mut received_amounts = Vec::with_capacity(entries.len());
+        for (receiver_id, amount) in entries.iter() {
+            let fee = self.internal_transfer_fee(&sender_id, receiver_id, amount.0);
+            self.internal_move(&sender_id, receiver_id, amount.0 - fee);
+            total_fee += fee;
+            received_amounts.push(U128(amount.0 - fee));
+        }
+
+        // 打印一条包含所有转账记录的标准 log
+        let events: Vec<FtTransfer> = entries
+            .iter()
+            .zip(received_amounts.iter())
+            .map(|((receiver_id, _), amount)| FtTransfer {
+                old_owner_id: &sender_id,
+                new_owner_id: receiver_id,
+                amount,
+                memo: memo.as_deref(),
+            })
+            .collect();
+        FtTransfer::emit_many(&events);
+
+        self.internal_charge_fee(&sender_id, total_fee);
+        self.internal_charge_storage(&sender_id, initial_storage_usage);
+    }
+}
+
+// 提前检查剩余 gas, 避免执行到一半因 gas 耗尽而失败
+fn assert_batch_gas(len: usize) {
+    require!(len > 0, "The batch should not be empty.");
+    let required_gas = GAS_PER_BATCH_ENTRY.0 * len as u64;
+    require!(
+        env::prepaid_gas().0 - env::used_gas().0 >= required_gas,
+        format!("Not enough gas for {} entries.", len)
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, Gas, ONE_YOCTO};
+
+    fn carol() -> AccountId {
+        "carol.near".parse().unwrap()
+    }
+
+    #[test]
+    fn test_mint_batch() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint_batch(
+            vec![
+                (alice(), U128(100 * ONE_TOKEN)),
+                (bob(), U128(200 * ONE_TOKEN)),
+            ],
+            Some("airdrop".to_string()),
+        );
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(200 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(300 * ONE_TOKEN));
+
+        // 只打印一条 log
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains(r#""event":"ft_mint""#));
+        assert!(logs[0].contains("alice.near") && logs[0].contains("bob.near"));
+    }
+
+    #[test]
+    fn test_transfer_batch() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint_batch(
+            vec![
+                (alice(), U128(ONE_TOKEN)),
+                (bob(), U128(1000 * ONE_TOKEN)),
+                (carol(), U128(ONE_TOKEN)),
+            ],
+            None,
+        );
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_batch(
+            vec![
+                (alice(), U128(100 * ONE_TOKEN)),
+                (carol(), U128(300 * ONE_TOKEN)),
+            ],
+            None,
+        );
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(600 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(alice()), U128(101 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(carol()), U128(301 * ONE_TOKEN));
+
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains(r#""event":"ft_transfer""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough gas for 2 entries.")]
+    fn test_mint_batch_without_enough_gas() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .prepaid_gas(Gas(5_000_000_000_000))
+            .build());
+
+        contract.mint_batch(
+            vec![(alice(), U128(ONE_TOKEN)), (bob(), U128(ONE_TOKEN))],
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only minter can call this method.")]
+    fn test_mint_batch_without_role() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .build());
+
+        contract.mint_batch(vec![(bob(), U128(ONE_TOKEN))], None);
+    }
+}
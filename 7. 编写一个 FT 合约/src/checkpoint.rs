@@ -0,0 +1,242 @@
+use crate::{Contract, ContractExt, StorageKey};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId, Balance, BlockHeight};
+
+// 记录某个区块结束时的数值, 用于查询历史余额和历史总供应量, 如 DAO 按提案区块统计投票权
+// 所有记录都永久保留, 新增记录的存储费由发起转账等调用的账户支付, 见 `storage`
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Checkpoint {
+    pub block_height: BlockHeight,
+    pub value: Balance,
+}
+
+#[near_bindgen]
+impl Contract {
+    // 查询账户在 `block_height` 区块结束时的余额
+    pub fn ft_balance_of_at(&self, account_id: AccountId, block_height: BlockHeight) -> U128 {
+        let value = self
+            .account_checkpoints
+            .get(&account_id)
+            .map(|checkpoints| checkpoint_value_at(&checkpoints, block_height))
+            .unwrap_or(0);
+        U128(value)
+    }
+
+    // 查询 `block_height` 区块结束时的总供应量
+    pub fn ft_total_supply_at(&self, block_height: BlockHeight) -> U128 {
+        U128(checkpoint_value_at(
+            &self.total_supply_checkpoints,
+            block_height,
+        ))
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 记录账户当前余额
+    pub(crate) fn internal_checkpoint_balance(&mut self, account_id: &AccountId) {
+        let balance = self.tokens.accounts.get(account_id).unwrap_or(0);
+        let mut checkpoints = self.account_checkpoints.get(account_id).unwrap_or_else(|| {
+            Vector::new(StorageKey::AccountCheckpoints {
+                account_hash: env::sha256(&account_id.try_to_vec().unwrap()),
+            })
+        });
+        if write_checkpoint(&mut checkpoints, balance) {
+            self.account_checkpoints.insert(account_id, &checkpoints);
+        }
+    }
+
+    // 记录当前总供应量
+    pub(crate) fn internal_checkpoint_total_supply(&mut self) {
+        let total_supply = self.tokens.total_supply;
+        write_checkpoint(&mut self.total_supply_checkpoints, total_supply);
+    }
+}
+
+// 同一区块内多次变化只保留最后的数值, 数值没有变化时不新增记录. 返回是否新增了记录
+fn write_checkpoint(checkpoints: &mut Vector<Checkpoint>, value: Balance) -> bool {
+    let block_height = env::block_height();
+    let checkpoint = Checkpoint {
+        block_height,
+        value,
+    };
+    let last = checkpoints
+        .len()
+        .checked_sub(1)
+        .map(|last_index| (last_index, checkpoints.get(last_index).unwrap()));
+    match last {
+        Some((last_index, last)) if last.block_height == block_height => {
+            checkpoints.replace(last_index, &checkpoint);
+            false
+        }
+        Some((_, last)) if last.value == value => false,
+        None if value == 0 => false,
+        _ => {
+            checkpoints.push(&checkpoint);
+            true
+        }
+    }
+}
+
+// 二分查找最后一个不晚于 `block_height` 的记录
+fn checkpoint_value_at(checkpoints: &Vector<Checkpoint>, block_height: BlockHeight) -> Balance {
+    let (mut low, mut high) = (0, checkpoints.len());
+    while low < high {
+        let mid = (low + high) / 2;
+        if checkpoints.get(mid).unwrap().block_height <= block_height {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        0
+    } else {
+        checkpoints.get(low - 1).unwrap().value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, contract_id, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{env, testing_env, ONE_YOCTO};
+
+    // 从第 `from_block` 个区块开始 Bob 每个区块给 Alice 转账 1 FT, 存储用量延续到下一个区块
+    fn transfer_every_block(contract: &mut Contract, from_block: u64, count: u64) {
+        for block_height in from_block..from_block + count {
+            testing_env!(VMContextBuilder::new()
+                .predecessor_account_id(bob())
+                .current_account_id(contract_id())
+                .attached_deposit(ONE_YOCTO)
+                .block_index(block_height)
+                .storage_usage(env::storage_usage())
+                .build());
+
+            contract.ft_transfer(alice(), U128(ONE_TOKEN), None);
+        }
+    }
+
+    // 给 Bob mint 1000 FT 并预存存储费, 给 Alice 注册
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .block_index(1)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(alice()), None);
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    #[test]
+    fn test_balance_and_total_supply_at() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        // 第 10 个区块给 Bob mint 1000 FT, 并给 Alice 注册
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .block_index(10)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(alice()), None);
+        deposit_storage(&mut contract, bob());
+
+        // 第 20 个区块 Bob 给 Alice 转账两次, 只记录区块结束时的余额
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_index(20)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+        contract.ft_transfer(alice(), U128(200 * ONE_TOKEN), None);
+
+        // 第 30 个区块 burn Bob 的 100 FT
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_index(30)
+            .build());
+
+        contract.burn(bob(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of_at(bob(), 9), U128(0));
+        assert_eq!(contract.ft_balance_of_at(bob(), 10), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(bob(), 19), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(bob(), 20), U128(700 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(bob(), 30), U128(600 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(bob(), 100), U128(600 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(alice(), 15), U128(0));
+        assert_eq!(
+            contract.ft_balance_of_at(alice(), 25),
+            U128(300 * ONE_TOKEN)
+        );
+
+        assert_eq!(contract.ft_total_supply_at(9), U128(0));
+        assert_eq!(contract.ft_total_supply_at(20), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply_at(30), U128(900 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(900 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_keep_all_checkpoints() {
+        let mut contract = setup();
+        transfer_every_block(&mut contract, 10, 40);
+
+        // 所有区块的历史余额都保留
+        assert_eq!(
+            contract.account_checkpoints.get(&alice()).unwrap().len(),
+            40
+        );
+        assert_eq!(contract.ft_balance_of_at(alice(), 10), U128(ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(alice(), 49), U128(40 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of_at(bob(), 10), U128(999 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply_at(1), U128(1000 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_checkpoint_storage_paid_by_sender() {
+        let mut contract = setup();
+        let available = contract.storage_balance_of(bob()).unwrap().available.0;
+        let storage_usage = env::storage_usage();
+        transfer_every_block(&mut contract, 10, 3);
+
+        // 新增的历史余额记录从 Bob 的可用存储费余额中扣除
+        let storage_cost =
+            (env::storage_usage() - storage_usage) as u128 * env::storage_byte_cost();
+        assert!(storage_cost > 0);
+        assert_eq!(
+            contract.storage_balance_of(bob()).unwrap().available.0,
+            available - storage_cost
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough storage balance")]
+    fn test_checkpoint_storage_not_covered() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .current_account_id(contract_id())
+            .attached_deposit(ONE_YOCTO)
+            .block_index(2)
+            .build());
+
+        contract.storage_withdraw(None);
+        transfer_every_block(&mut contract, 10, 1);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::events::{AccountBlock, AccountUnblock, AllowlistModeUpdate, AllowlistUpdate, Seize};
+use crate::{Contract, ContractExt};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+// 合规模式: 合约所有者维护封禁名单和可选的白名单
+// 被封禁或不在白名单中 (白名单模式开启时) 的账户不能发送, 接收, 被 mint 和注册
+#[near_bindgen]
+impl Contract {
+    // 合约所有者能封禁账户
+    pub fn block_account(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(
+            self.blocked_accounts.insert(&account_id),
+            "The account is already blocked."
+        );
+
+        // 打印事件 log
+        AccountBlock {
+            account_id: &account_id,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // 合约所有者能解除封禁
+    pub fn unblock_account(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(
+            self.blocked_accounts.remove(&account_id),
+            "The account is not blocked."
+        );
+
+        // 打印事件 log
+        AccountUnblock {
+            account_id: &account_id,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // 合约所有者能开启或关闭白名单模式
+    pub fn set_allowlist_enabled(&mut self, enabled: bool) {
+        self.assert_owner();
+        require!(
+            self.allowlist_enabled != enabled,
+            "Allowlist mode is not changed."
+        );
+        self.allowlist_enabled = enabled;
+
+        // 打印事件 log
+        AllowlistModeUpdate {
+            enabled,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // 合约所有者能把账户加入或移出白名单
+    pub fn set_allowed(&mut self, account_id: AccountId, allowed: bool) {
+        self.assert_owner();
+        let changed = if allowed {
+            self.allowed_accounts.insert(&account_id)
+        } else {
+            self.allowed_accounts.remove(&account_id)
+        };
+        require!(changed, "Allowlist is not changed.");
+
+        // 打印事件 log
+        AllowlistUpdate {
+            account_id: &account_id,
+            allowed,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // 合约所有者能没收被封禁账户的全部余额
+    // `receiver_id` 为 `None` 时 burn 掉余额, 否则转给 `receiver_id`
+    pub fn seize(
+        &mut self,
+        account_id: AccountId,
+        receiver_id: Option<AccountId>,
+        memo: Option<String>,
+    ) -> U128 {
+        self.assert_owner();
+        require!(
+            self.blocked_accounts.contains(&account_id),
+            "Only blocked account can be seized."
+        );
+        let amount = self.tokens.accounts.get(&account_id).unwrap_or(0);
+        require!(amount > 0, "The account has no balance to seize.");
+
+        match &receiver_id {
+            Some(receiver_id) => {
+                self.assert_compliant(receiver_id);
+                if !self.tokens.accounts.contains_key(receiver_id) {
+                    self.tokens.internal_register_account(receiver_id);
+                }
+                self.internal_transfer_unchecked(&account_id, receiver_id, amount, memo);
+            }
+            None => self.internal_burn(&account_id, amount, memo),
+        }
+
+        // 打印事件 log
+        Seize {
+            account_id: &account_id,
+            receiver_id: receiver_id.as_ref(),
+            amount: &U128(amount),
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+
+        U128(amount)
+    }
+
+    pub fn is_blocked(&self, account_id: AccountId) -> bool {
+        self.blocked_accounts.contains(&account_id)
+    }
+
+    pub fn is_allowlist_enabled(&self) -> bool {
+        self.allowlist_enabled
+    }
+
+    pub fn is_allowed(&self, account_id: AccountId) -> bool {
+        self.allowed_accounts.contains(&account_id)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn assert_compliant(&self, account_id: &AccountId) {
+        require!(
+            !self.blocked_accounts.contains(account_id),
+            format!("The account {} is blocked.", account_id)
+        );
+        if self.allowlist_enabled {
+            require!(
+                self.allowed_accounts.contains(account_id),
+                format!("The account {} is not allowlisted.", account_id)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, ONE_YOCTO};
+
+    fn carol() -> AccountId {
+        "carol.near".parse().unwrap()
+    }
+
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(alice()), None);
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob.near is blocked.")]
+    fn test_transfer_from_blocked_account() {
+        let mut contract = setup();
+
+        contract.block_account(bob());
+
+        assert!(contract.is_blocked(bob()));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account alice.near is blocked.")]
+    fn test_transfer_from_by_blocked_spender() {
+        let mut contract = setup();
+
+        contract.internal_set_allowance(&bob(), &alice(), 100 * ONE_TOKEN);
+        contract.block_account(alice());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_from(bob(), owner(), U128(100 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account carol.near is blocked.")]
+    fn test_storage_deposit_for_blocked_account() {
+        let mut contract = setup();
+
+        contract.block_account(carol());
+        contract.storage_deposit(Some(carol()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account carol.near is not allowlisted.")]
+    fn test_mint_in_allowlist_mode() {
+        let mut contract = setup();
+
+        contract.set_allowlist_enabled(true);
+        contract.set_allowed(alice(), true);
+        contract.mint(alice(), U128(ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(ONE_TOKEN));
+
+        contract.mint(carol(), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_seize_to_receiver() {
+        let mut contract = setup();
+
+        contract.block_account(bob());
+        let amount = contract.seize(bob(), Some(owner()), Some("sanctioned".to_string()));
+
+        assert_eq!(amount, U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(0));
+        assert_eq!(contract.ft_balance_of(owner()), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(1000 * ONE_TOKEN));
+        assert!(get_logs().last().unwrap().contains(r#""event":"seize""#));
+    }
+
+    #[test]
+    fn test_seize_and_burn() {
+        let mut contract = setup();
+
+        contract.block_account(bob());
+        contract.seize(bob(), None, None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(0));
+        assert_eq!(contract.ft_total_supply(), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only blocked account can be seized.")]
+    fn test_seize_unblocked_account() {
+        let mut contract = setup();
+
+        contract.seize(bob(), None, None);
+    }
+}
@@ -0,0 +1,347 @@
+use crate::events::{DividendClaim, DividendDistribution};
+use crate::u256::U256;
+use crate::{Contract, ContractExt, StorageKey};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, require, AccountId, Balance, Promise};
+
+// 每个 FT 累计分红的放大倍数, 避免分红数量小于总供应量时精度丢失
+const DIVIDEND_PRECISION: u128 = 1_000_000_000_000_000_000_000_000;
+
+// 按持有比例分配 NEAR 分红. 不遍历持有者, 而是记录每个 FT 累计分到的 NEAR,
+// 账户余额变化前先结算此前的分红, 结算后的分红在领取之前保存在 `pending` 中
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DividendPool {
+    // 每个 FT 累计分到的 NEAR, 乘以 `DIVIDEND_PRECISION`
+    per_token: [u64; 4],
+    // 累计分配的 NEAR
+    total_distributed: Balance,
+    accounts: LookupMap<AccountId, DividendAccount>,
+}
+
+// 没有记录的账户表示自分红开始后余额从未变化, 相当于 `per_token_paid` 为 0
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct DividendAccount {
+    // 上次结算时的 `per_token`
+    per_token_paid: [u64; 4],
+    // 已结算但未领取的 NEAR
+    pending: Balance,
+}
+
+impl DividendPool {
+    pub fn new() -> Self {
+        Self {
+            per_token: [0; 4],
+            total_distributed: 0,
+            accounts: LookupMap::new(StorageKey::DividendAccounts),
+        }
+    }
+
+    // 计算账户截至目前的分红, 包括未结算的部分
+    fn dividends_of(&self, account: &DividendAccount, balance: Balance) -> Balance {
+        let per_token = U256(self.per_token) - U256(account.per_token_paid);
+        let earned = per_token * U256::from(balance) / U256::from(DIVIDEND_PRECISION);
+        account.pending + earned.as_u128()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // 把附加的 NEAR 按当前余额比例分配给所有持有者
+    // 合约账户持有的 FT 不参与分红, 其中质押的部分按质押数量分给质押者
+    #[payable]
+    pub fn distribute(&mut self) {
+        let amount = env::attached_deposit();
+        require!(amount > 0, "Requires attached deposit to distribute.");
+        let contract_balance = self
+            .tokens
+            .accounts
+            .get(&env::current_account_id())
+            .unwrap_or(0);
+        let total_supply =
+            self.tokens.total_supply - contract_balance + self.internal_total_staked();
+        require!(total_supply > 0, "There are no holders to distribute to.");
+
+        let per_token =
+            U256::from(amount) * U256::from(DIVIDEND_PRECISION) / U256::from(total_supply);
+        self.dividends.per_token = (U256(self.dividends.per_token) + per_token).0;
+        self.dividends.total_distributed += amount;
+
+        // 打印事件 log
+        DividendDistribution {
+            by: &env::predecessor_account_id(),
+            amount: &U128(amount),
+        }
+        .emit();
+    }
+
+    // 领取全部分红. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn claim_dividends(&mut self) -> Promise {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+        self.internal_settle_dividends(&account_id);
+
+        let mut account = self.dividends.accounts.get(&account_id).unwrap_or_default();
+        let amount = account.pending;
+        require!(amount > 0, "No dividends to claim.");
+        account.pending = 0;
+        self.dividends.accounts.insert(&account_id, &account);
+        self.internal_remove_settled_dividends(&account_id);
+        self.internal_charge_storage(&account_id, initial_storage_usage);
+
+        // 打印事件 log
+        DividendClaim {
+            account_id: &account_id,
+            amount: &U128(amount),
+        }
+        .emit();
+
+        Promise::new(account_id).transfer(amount)
+    }
+
+    pub fn pending_dividends(&self, account_id: AccountId) -> U128 {
+        let account = self.dividends.accounts.get(&account_id).unwrap_or_default();
+        let balance = self.internal_dividend_balance(&account_id);
+        U128(self.dividends.dividends_of(&account, balance))
+    }
+
+    // 查询累计分配的 NEAR
+    pub fn total_dividends_distributed(&self) -> U128 {
+        U128(self.dividends.total_distributed)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 参与分红的余额, 包括质押的 FT. 合约账户持有的 FT 已计入质押者, 因此合约账户不参与分红
+    fn internal_dividend_balance(&self, account_id: &AccountId) -> Balance {
+        if *account_id == env::current_account_id() {
+            return 0;
+        }
+        self.tokens.accounts.get(account_id).unwrap_or(0) + self.internal_staked_balance(account_id)
+    }
+
+    // 按账户当前余额结算分红, 必须在账户余额或质押数量变化之前调用
+    pub(crate) fn internal_settle_dividends(&mut self, account_id: &AccountId) {
+        let mut account = self.dividends.accounts.get(account_id).unwrap_or_default();
+        if account.per_token_paid == self.dividends.per_token
+            || *account_id == env::current_account_id()
+        {
+            return;
+        }
+        let balance = self.internal_dividend_balance(account_id);
+        account.pending = self.dividends.dividends_of(&account, balance);
+        account.per_token_paid = self.dividends.per_token;
+        self.dividends.accounts.insert(account_id, &account);
+    }
+
+    // 未注册账户的存储费已退还, 分红领完且没有质押时删除其记录
+    pub(crate) fn internal_remove_settled_dividends(&mut self, account_id: &AccountId) {
+        if self.tokens.accounts.contains_key(account_id)
+            || self.internal_staked_balance(account_id) > 0
+        {
+            return;
+        }
+        let account = self.dividends.accounts.get(account_id);
+        if account.filter(|account| account.pending == 0).is_some() {
+            self.dividends.accounts.remove(account_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, contract_id, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, Balance, ONE_NEAR, ONE_YOCTO};
+
+    fn carol() -> AccountId {
+        "carol.near".parse().unwrap()
+    }
+
+    // Bob 持有 300 FT, Alice 持有 100 FT, 两人都预存了存储费
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(300 * ONE_TOKEN), None);
+        contract.mint(alice(), U128(100 * ONE_TOKEN), None);
+        deposit_storage(&mut contract, bob());
+        deposit_storage(&mut contract, alice());
+        contract
+    }
+
+    fn distribute(contract: &mut Contract, amount: Balance) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(owner())
+            .attached_deposit(amount)
+            .build());
+
+        contract.distribute();
+    }
+
+    fn transfer(
+        contract: &mut Contract,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: Balance,
+    ) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(sender_id)
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(receiver_id, U128(amount), None);
+    }
+
+    // 领取分红并返回转出的 NEAR 数量
+    fn claim(contract: &mut Contract, account_id: AccountId) -> Balance {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(account_id)
+            .attached_deposit(ONE_YOCTO)
+            .account_balance(1_000_000 * ONE_NEAR)
+            .build());
+
+        contract.claim_dividends();
+        match &get_created_receipts()[0].actions[..] {
+            [VmAction::Transfer { deposit }] => *deposit,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_distribute_and_claim() {
+        let mut contract = setup();
+
+        distribute(&mut contract, 4 * ONE_NEAR);
+
+        assert_eq!(contract.pending_dividends(bob()), U128(3 * ONE_NEAR));
+        assert_eq!(contract.pending_dividends(alice()), U128(ONE_NEAR));
+
+        // 转账后分红按新的余额计算, 之前的分红保持不变
+        transfer(&mut contract, bob(), alice(), 200 * ONE_TOKEN);
+        distribute(&mut contract, 4 * ONE_NEAR);
+
+        assert_eq!(contract.pending_dividends(bob()), U128(4 * ONE_NEAR));
+        assert_eq!(contract.pending_dividends(alice()), U128(4 * ONE_NEAR));
+
+        assert_eq!(claim(&mut contract, bob()), 4 * ONE_NEAR);
+        assert_eq!(contract.pending_dividends(bob()), U128(0));
+        assert_eq!(contract.total_dividends_distributed(), U128(8 * ONE_NEAR));
+    }
+
+    #[test]
+    fn test_dividends_after_unregister() {
+        let mut contract = setup();
+
+        distribute(&mut contract, 4 * ONE_NEAR);
+
+        // 注销后 burn 掉的 FT 不再参与分红, 但已获得的分红仍可领取
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.storage_unregister(Some(true));
+        distribute(&mut contract, 3 * ONE_NEAR);
+
+        assert_eq!(contract.pending_dividends(bob()), U128(6 * ONE_NEAR));
+        assert_eq!(claim(&mut contract, alice()), ONE_NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "No dividends to claim.")]
+    fn test_claim_without_dividends() {
+        let mut contract = setup();
+
+        claim(&mut contract, carol());
+    }
+
+    // 随机执行转账, mint, burn 和分红, 所有账户领取的分红之和等于分配的总量, 误差仅来自每次结算时的向下取整
+    #[test]
+    fn test_claims_sum_to_distributed() {
+        let mut contract = setup();
+        let accounts = [bob(), alice(), carol()];
+
+        deposit_storage(&mut contract, carol());
+
+        let mut seed: u64 = 42;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+
+        let mut distributed = 0;
+        let mut operations = 0;
+        for _ in 0..200 {
+            let index = next(3) as usize;
+            let account_id = accounts[index].clone();
+            let balance = contract.ft_balance_of(account_id.clone()).0;
+            match next(4) {
+                0 => {
+                    let amount = next(1_000_000) as u128 * ONE_NEAR / 1000 + 1;
+                    distribute(&mut contract, amount);
+                    distributed += amount;
+                }
+                1 if balance > 0 => {
+                    let receiver_id = accounts[(index + 1 + next(2) as usize) % 3].clone();
+                    transfer(
+                        &mut contract,
+                        account_id,
+                        receiver_id,
+                        balance / (next(4) as u128 + 1),
+                    );
+                }
+                2 => {
+                    testing_env!(VMContextBuilder::new()
+                        .current_account_id(contract_id())
+                        .predecessor_account_id(owner())
+                        .build());
+
+                    contract.mint(account_id, U128(next(1000) as u128 * ONE_TOKEN + 1), None);
+                }
+                3 if balance > 0 => {
+                    testing_env!(VMContextBuilder::new()
+                        .current_account_id(contract_id())
+                        .predecessor_account_id(account_id)
+                        .attached_deposit(ONE_YOCTO)
+                        .build());
+
+                    contract.ft_burn(U128(balance / (next(4) as u128 + 1)), None);
+                }
+                _ => {}
+            }
+            operations += 1;
+        }
+
+        let mut claimed = 0;
+        for account_id in accounts.iter() {
+            if contract.pending_dividends(account_id.clone()).0 > 0 {
+                claimed += claim(&mut contract, account_id.clone());
+            }
+        }
+
+        assert!(distributed > 0);
+        assert!(claimed <= distributed);
+        assert!(distributed - claimed <= operations * accounts.len() as u128);
+    }
+}
@@ -0,0 +1,839 @@
+use crate::pause::Feature;
+use crate::roles::Role;
+use crate::vesting::VestingSchedule;
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::json_types::{Base58CryptoHash, U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::{env, serde_json, AccountId, PublicKey};
+
+// 自定义事件遵循 NEP297 格式, 标准名为 `hello_ft`
+// EVENT_JSON:{"standard":"hello_ft","version":"1.0.0","event":"...","data":{...}}
+pub const EVENT_STANDARD: &str = "hello_ft";
+pub const EVENT_VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a HelloFtEvent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum HelloFtEvent<'a> {
+    MetadataUpdate(MetadataUpdate<'a>),
+    MaxSupplyUpdate(MaxSupplyUpdate<'a>),
+    RoleGrant(RoleGrant<'a>),
+    RoleRevoke(RoleRevoke<'a>),
+    OwnershipProposal(OwnershipProposal<'a>),
+    OwnershipProposalCancel(OwnershipProposalCancel<'a>),
+    OwnershipTransfer(OwnershipTransfer<'a>),
+    Pause(Pause<'a>),
+    Unpause(Unpause<'a>),
+    AccountFreeze(AccountFreeze<'a>),
+    AccountUnfreeze(AccountUnfreeze<'a>),
+    Approval(Approval<'a>),
+    VestingCreate(VestingCreate<'a>),
+    VestingRevoke(VestingRevoke<'a>),
+    TransferFeeUpdate(TransferFeeUpdate),
+    TreasuryUpdate(TreasuryUpdate<'a>),
+    FeeExemptionUpdate(FeeExemptionUpdate<'a>),
+    TransferFeeCharge(TransferFeeCharge<'a>),
+    AccountBlock(AccountBlock<'a>),
+    AccountUnblock(AccountUnblock<'a>),
+    AllowlistModeUpdate(AllowlistModeUpdate<'a>),
+    AllowlistUpdate(AllowlistUpdate<'a>),
+    Seize(Seize<'a>),
+    RegistrationSponsorUpdate(RegistrationSponsorUpdate<'a>),
+    SponsoredRegistration(SponsoredRegistration<'a>),
+    PermitKeyUpdate(PermitKeyUpdate<'a>),
+    MintQuotaUpdate(MintQuotaUpdate<'a>),
+    EpochMintCapUpdate(EpochMintCapUpdate<'a>),
+    ContractUpgrade(ContractUpgrade<'a>),
+    DividendDistribution(DividendDistribution<'a>),
+    DividendClaim(DividendClaim<'a>),
+    StakingRewardRateUpdate(StakingRewardRateUpdate<'a>),
+    Stake(Stake<'a>),
+    Unstake(Unstake<'a>),
+    StakingRewardClaim(StakingRewardClaim<'a>),
+    HolderBackfillComplete(HolderBackfillComplete<'a>),
+}
+
+impl HelloFtEvent<'_> {
+    pub fn emit(&self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_VERSION,
+            event: self,
+        };
+        env::log_str(&format!(
+            "EVENT_JSON:{}",
+            serde_json::to_string(&log).unwrap()
+        ));
+    }
+}
+
+// FT 元数据被修改
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataUpdate<'a> {
+    pub metadata: &'a FungibleTokenMetadata,
+    pub version: U64,
+}
+
+impl MetadataUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::MetadataUpdate(self).emit()
+    }
+}
+
+// FT 供应量上限被修改
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MaxSupplyUpdate<'a> {
+    pub old_max_supply: Option<&'a U128>,
+    pub new_max_supply: &'a U128,
+}
+
+impl MaxSupplyUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::MaxSupplyUpdate(self).emit()
+    }
+}
+
+// 账户被授予角色
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoleGrant<'a> {
+    pub account_id: &'a AccountId,
+    pub role: Role,
+    pub by: &'a AccountId,
+}
+
+impl RoleGrant<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::RoleGrant(self).emit()
+    }
+}
+
+// 账户的角色被撤销或主动放弃
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoleRevoke<'a> {
+    pub account_id: &'a AccountId,
+    pub role: Role,
+    pub by: &'a AccountId,
+}
+
+impl RoleRevoke<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::RoleRevoke(self).emit()
+    }
+}
+
+// 合约所有者提议了新的所有者
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnershipProposal<'a> {
+    pub owner_id: &'a AccountId,
+    pub pending_owner_id: &'a AccountId,
+}
+
+impl OwnershipProposal<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::OwnershipProposal(self).emit()
+    }
+}
+
+// 合约所有者撤回了所有权提议
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnershipProposalCancel<'a> {
+    pub owner_id: &'a AccountId,
+    pub pending_owner_id: &'a AccountId,
+}
+
+impl OwnershipProposalCancel<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::OwnershipProposalCancel(self).emit()
+    }
+}
+
+// 合约所有权发生转移, `new_owner_id` 为 `None` 表示所有权被放弃
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnershipTransfer<'a> {
+    pub old_owner_id: Option<&'a AccountId>,
+    pub new_owner_id: Option<&'a AccountId>,
+}
+
+impl OwnershipTransfer<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::OwnershipTransfer(self).emit()
+    }
+}
+
+// 功能被暂停
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Pause<'a> {
+    pub feature: Feature,
+    pub by: &'a AccountId,
+}
+
+impl Pause<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::Pause(self).emit()
+    }
+}
+
+// 功能被恢复
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Unpause<'a> {
+    pub feature: Feature,
+    pub by: &'a AccountId,
+}
+
+impl Unpause<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::Unpause(self).emit()
+    }
+}
+
+// 账户被冻结
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountFreeze<'a> {
+    pub account_id: &'a AccountId,
+    pub by: &'a AccountId,
+}
+
+impl AccountFreeze<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::AccountFreeze(self).emit()
+    }
+}
+
+// 账户被解冻
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountUnfreeze<'a> {
+    pub account_id: &'a AccountId,
+    pub by: &'a AccountId,
+}
+
+impl AccountUnfreeze<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::AccountUnfreeze(self).emit()
+    }
+}
+
+// 授权额度被设置
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Approval<'a> {
+    pub owner_id: &'a AccountId,
+    pub spender_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl Approval<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::Approval(self).emit()
+    }
+}
+
+// 账户获得了线性释放计划
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingCreate<'a> {
+    pub account_id: &'a AccountId,
+    pub schedule: &'a VestingSchedule,
+}
+
+impl VestingCreate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::VestingCreate(self).emit()
+    }
+}
+
+// 线性释放计划被撤销, 尚未释放的 FT 被转回金库
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingRevoke<'a> {
+    pub account_id: &'a AccountId,
+    pub treasury_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl VestingRevoke<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::VestingRevoke(self).emit()
+    }
+}
+
+// 转账手续费率被修改, 单位为万分之一
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferFeeUpdate {
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+}
+
+impl TransferFeeUpdate {
+    pub fn emit(self) {
+        HelloFtEvent::TransferFeeUpdate(self).emit()
+    }
+}
+
+// 收取手续费的金库账户被修改
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TreasuryUpdate<'a> {
+    pub old_treasury_id: Option<&'a AccountId>,
+    pub new_treasury_id: Option<&'a AccountId>,
+}
+
+impl TreasuryUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::TreasuryUpdate(self).emit()
+    }
+}
+
+// 账户被加入或移出免手续费名单
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeExemptionUpdate<'a> {
+    pub account_id: &'a AccountId,
+    pub exempt: bool,
+}
+
+impl FeeExemptionUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::FeeExemptionUpdate(self).emit()
+    }
+}
+
+// 转账时收取了手续费
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferFeeCharge<'a> {
+    pub sender_id: &'a AccountId,
+    pub treasury_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl TransferFeeCharge<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::TransferFeeCharge(self).emit()
+    }
+}
+
+// 账户被封禁
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountBlock<'a> {
+    pub account_id: &'a AccountId,
+    pub by: &'a AccountId,
+}
+
+impl AccountBlock<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::AccountBlock(self).emit()
+    }
+}
+
+// 账户被解除封禁
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountUnblock<'a> {
+    pub account_id: &'a AccountId,
+    pub by: &'a AccountId,
+}
+
+impl AccountUnblock<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::AccountUnblock(self).emit()
+    }
+}
+
+// 白名单模式被开启或关闭
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AllowlistModeUpdate<'a> {
+    pub enabled: bool,
+    pub by: &'a AccountId,
+}
+
+impl AllowlistModeUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::AllowlistModeUpdate(self).emit()
+    }
+}
+
+// 账户被加入或移出白名单
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AllowlistUpdate<'a> {
+    pub account_id: &'a AccountId,
+    pub allowed: bool,
+    pub by: &'a AccountId,
+}
+
+impl AllowlistUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::AllowlistUpdate(self).emit()
+    }
+}
+
+// 被封禁账户的余额被没收, `receiver_id` 为 `None` 表示被 burn
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Seize<'a> {
+    pub account_id: &'a AccountId,
+    pub receiver_id: Option<&'a AccountId>,
+    pub amount: &'a U128,
+    pub by: &'a AccountId,
+}
+
+impl Seize<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::Seize(self).emit()
+    }
+}
+
+// 代付注册的预算和限流被修改
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RegistrationSponsorUpdate<'a> {
+    pub budget: &'a U128,
+    pub max_per_sender: u32,
+    pub period: &'a U64,
+}
+
+impl RegistrationSponsorUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::RegistrationSponsorUpdate(self).emit()
+    }
+}
+
+// 合约为接收者代付了存储费
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SponsoredRegistration<'a> {
+    pub account_id: &'a AccountId,
+    pub sender_id: &'a AccountId,
+    pub cost: &'a U128,
+}
+
+impl SponsoredRegistration<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::SponsoredRegistration(self).emit()
+    }
+}
+
+// 签名授权用的 key 被注册或注销, `public_key` 为 `None` 表示注销
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PermitKeyUpdate<'a> {
+    pub account_id: &'a AccountId,
+    pub public_key: Option<&'a PublicKey>,
+}
+
+impl PermitKeyUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::PermitKeyUpdate(self).emit()
+    }
+}
+
+// minter 的 mint 额度被修改, `limit` 为 `None` 表示移除额度限制
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintQuotaUpdate<'a> {
+    pub minter_id: &'a AccountId,
+    pub limit: Option<&'a U128>,
+    pub window: Option<&'a U64>,
+}
+
+impl MintQuotaUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::MintQuotaUpdate(self).emit()
+    }
+}
+
+// 每个 epoch 的 mint 上限被修改
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EpochMintCapUpdate<'a> {
+    pub old_epoch_mint_cap: Option<&'a U128>,
+    pub new_epoch_mint_cap: Option<&'a U128>,
+}
+
+impl EpochMintCapUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::EpochMintCapUpdate(self).emit()
+    }
+}
+
+// 合约代码升级结果. 升级失败时部署被回滚, 合约代码仍为 `old_code_hash`
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractUpgrade<'a> {
+    pub old_code_hash: Option<&'a Base58CryptoHash>,
+    pub new_code_hash: &'a Base58CryptoHash,
+    pub success: bool,
+}
+
+impl ContractUpgrade<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::ContractUpgrade(self).emit()
+    }
+}
+
+// 分配 NEAR 分红
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DividendDistribution<'a> {
+    pub by: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl DividendDistribution<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::DividendDistribution(self).emit()
+    }
+}
+
+// 领取 NEAR 分红
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DividendClaim<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl DividendClaim<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::DividendClaim(self).emit()
+    }
+}
+
+// 更新质押奖励速率
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingRewardRateUpdate<'a> {
+    pub old_reward_rate: &'a U128,
+    pub new_reward_rate: &'a U128,
+}
+
+impl StakingRewardRateUpdate<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::StakingRewardRateUpdate(self).emit()
+    }
+}
+
+// 质押 FT
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Stake<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl Stake<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::Stake(self).emit()
+    }
+}
+
+// 取回质押的 FT
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Unstake<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl Unstake<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::Unstake(self).emit()
+    }
+}
+
+// 领取质押奖励
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingRewardClaim<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl StakingRewardClaim<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::StakingRewardClaim(self).emit()
+    }
+}
+
+// 持有者索引补全完成, 之后可以查询持有者
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HolderBackfillComplete<'a> {
+    pub by: &'a AccountId,
+}
+
+impl HolderBackfillComplete<'_> {
+    pub fn emit(self) {
+        HelloFtEvent::HolderBackfillComplete(self).emit()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::events::{EVENT_STANDARD, EVENT_VERSION};
+    use crate::pause::Feature;
+    use crate::roles::Role;
+    use crate::test::{alice, bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::serde_json::{self, json, Value};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, ONE_YOCTO};
+
+    // 解析当前 log 中所有 `hello_ft` 标准的事件, 返回 (事件名, 数据)
+    fn events() -> Vec<(String, Value)> {
+        get_logs()
+            .iter()
+            .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .map(|log| serde_json::from_str::<Value>(log).unwrap())
+            .filter(|log| log["standard"] == EVENT_STANDARD)
+            .map(|log| {
+                assert_eq!(log["version"], EVENT_VERSION);
+                (
+                    log["event"].as_str().unwrap().to_string(),
+                    log["data"].clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn event(name: &str, data: Value) -> (String, Value) {
+        (name.to_string(), data)
+    }
+
+    fn call_as(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(ONE_YOCTO)
+            .build());
+    }
+
+    fn setup() -> Contract {
+        let contract = Contract::init(owner(), metadata(), None, None);
+        call_as(owner());
+        contract
+    }
+
+    #[test]
+    fn test_init_events() {
+        call_as(owner());
+
+        Contract::init(owner(), metadata(), Some(U128(1000 * ONE_TOKEN)), None);
+
+        let events = events();
+        assert_eq!(
+            events[0],
+            event(
+                "ownership_transfer",
+                json!({ "old_owner_id": null, "new_owner_id": "owner.near" })
+            )
+        );
+        assert_eq!(events[1].0, "metadata_update");
+        assert_eq!(events[1].1["version"], "0");
+        assert_eq!(events[1].1["metadata"]["symbol"], "HelloFT");
+        assert_eq!(
+            events[2],
+            event(
+                "max_supply_update",
+                json!({ "old_max_supply": null, "new_max_supply": "1000000000000000000000" })
+            )
+        );
+        for (event, role) in
+            events[3..]
+                .iter()
+                .zip(["minter", "burner", "pauser", "metadata_admin"])
+        {
+            assert_eq!(
+                *event,
+                (
+                    "role_grant".to_string(),
+                    json!({ "account_id": "owner.near", "role": role, "by": "owner.near" })
+                )
+            );
+        }
+        assert_eq!(events.len(), 7);
+    }
+
+    #[test]
+    fn test_ownership_events() {
+        let mut contract = setup();
+
+        contract.propose_owner(bob());
+        call_as(bob());
+        contract.accept_ownership();
+
+        // 原所有者的初始角色随所有权转移
+        let roles = ["minter", "burner", "pauser", "metadata_admin"];
+        let mut expected = vec![event(
+            "ownership_transfer",
+            json!({ "old_owner_id": "owner.near", "new_owner_id": "bob.near" }),
+        )];
+        for role in roles {
+            expected.push(event(
+                "role_revoke",
+                json!({ "account_id": "owner.near", "role": role, "by": "bob.near" }),
+            ));
+            expected.push(event(
+                "role_grant",
+                json!({ "account_id": "bob.near", "role": role, "by": "bob.near" }),
+            ));
+        }
+        assert_eq!(events(), expected);
+
+        call_as(bob());
+        contract.renounce_ownership();
+
+        let mut expected = vec![event(
+            "ownership_transfer",
+            json!({ "old_owner_id": "bob.near", "new_owner_id": null }),
+        )];
+        for role in roles {
+            expected.push(event(
+                "role_revoke",
+                json!({ "account_id": "bob.near", "role": role, "by": "bob.near" }),
+            ));
+        }
+        assert_eq!(events(), expected);
+    }
+
+    #[test]
+    fn test_ownership_proposal_events() {
+        let mut contract = setup();
+
+        contract.propose_owner(alice());
+        contract.cancel_ownership_proposal();
+
+        let data = json!({ "owner_id": "owner.near", "pending_owner_id": "alice.near" });
+        assert_eq!(
+            events(),
+            vec![
+                event("ownership_proposal", data.clone()),
+                event("ownership_proposal_cancel", data),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata_events() {
+        let mut contract = setup();
+
+        contract.set_icon(Some("data:image/svg+xml,<svg></svg>".to_string()));
+
+        let events = events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "metadata_update");
+        assert_eq!(events[0].1["version"], "1");
+        assert_eq!(
+            events[0].1["metadata"]["icon"],
+            "data:image/svg+xml,<svg></svg>"
+        );
+    }
+
+    #[test]
+    fn test_pause_events() {
+        let mut contract = setup();
+
+        contract.pause(Feature::Transfer);
+        contract.unpause(Feature::Transfer);
+        contract.freeze_account(alice());
+        contract.unfreeze_account(alice());
+
+        assert_eq!(
+            events(),
+            vec![
+                event(
+                    "pause",
+                    json!({ "feature": "transfer", "by": "owner.near" })
+                ),
+                event(
+                    "unpause",
+                    json!({ "feature": "transfer", "by": "owner.near" })
+                ),
+                event(
+                    "account_freeze",
+                    json!({ "account_id": "alice.near", "by": "owner.near" })
+                ),
+                event(
+                    "account_unfreeze",
+                    json!({ "account_id": "alice.near", "by": "owner.near" })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_role_events() {
+        let mut contract = setup();
+
+        contract.grant_role(alice(), Role::Minter);
+        // 重复授予不改变状态, 也不打印事件
+        contract.grant_role(alice(), Role::Minter);
+        contract.revoke_role(alice(), Role::Minter);
+
+        let data = json!({ "account_id": "alice.near", "role": "minter", "by": "owner.near" });
+        assert_eq!(
+            events(),
+            vec![
+                event("role_grant", data.clone()),
+                event("role_revoke", data)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cap_events() {
+        let mut contract = Contract::init(owner(), metadata(), Some(U128(2000)), None);
+        call_as(owner());
+
+        contract.lower_max_supply(U128(1000));
+        contract.set_epoch_mint_cap(Some(U128(100)));
+        contract.set_mint_quota(alice(), U128(10), U64(60));
+        contract.remove_mint_quota(alice());
+
+        assert_eq!(
+            events(),
+            vec![
+                event(
+                    "max_supply_update",
+                    json!({ "old_max_supply": "2000", "new_max_supply": "1000" })
+                ),
+                event(
+                    "epoch_mint_cap_update",
+                    json!({ "old_epoch_mint_cap": null, "new_epoch_mint_cap": "100" })
+                ),
+                event(
+                    "mint_quota_update",
+                    json!({ "minter_id": "alice.near", "limit": "10", "window": "60" })
+                ),
+                event(
+                    "mint_quota_update",
+                    json!({ "minter_id": "alice.near", "limit": null, "window": null })
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::events::{FeeExemptionUpdate, TransferFeeCharge, TransferFeeUpdate, TreasuryUpdate};
+use crate::{Contract, ContractExt};
+use near_sdk::json_types::U128;
+use near_sdk::{near_bindgen, require, AccountId, Balance};
+
+// 转账手续费率上限, 即 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+const FEE_DENOMINATOR: Balance = 10_000;
+
+// 转账手续费: 按万分之 `fee_bps` 从转账数量中扣除, 转入金库账户
+#[near_bindgen]
+impl Contract {
+    pub fn ft_transfer_fee(&self) -> u16 {
+        self.fee_bps
+    }
+
+    pub fn treasury_id(&self) -> Option<AccountId> {
+        self.treasury_id.clone()
+    }
+
+    pub fn is_fee_exempt(&self, account_id: AccountId) -> bool {
+        self.fee_exempt_accounts.contains(&account_id)
+    }
+
+    // 合约所有者能修改手续费率, 不能超过 `MAX_FEE_BPS`
+    pub fn set_transfer_fee(&mut self, fee_bps: u16) {
+        self.assert_owner();
+        require!(
+            fee_bps <= MAX_FEE_BPS,
+            format!("Transfer fee can not exceed {} basis points.", MAX_FEE_BPS)
+        );
+        let old_fee_bps = std::mem::replace(&mut self.fee_bps, fee_bps);
+
+        // 打印事件 log
+        TransferFeeUpdate {
+            old_fee_bps,
+            new_fee_bps: fee_bps,
+        }
+        .emit();
+    }
+
+    // 合约所有者能修改金库账户, 设置为 `None` 时停止收取手续费
+    pub fn set_treasury(&mut self, treasury_id: Option<AccountId>) {
+        self.assert_owner();
+        let old_treasury_id = std::mem::replace(&mut self.treasury_id, treasury_id);
+
+        // 打印事件 log
+        TreasuryUpdate {
+            old_treasury_id: old_treasury_id.as_ref(),
+            new_treasury_id: self.treasury_id.as_ref(),
+        }
+        .emit();
+    }
+
+    // 合约所有者能把账户加入或移出免手续费名单, 名单中的账户作为发送者或接收者时都不收取手续费
+    pub fn set_fee_exempt(&mut self, account_id: AccountId, exempt: bool) {
+        self.assert_owner();
+        let changed = if exempt {
+            self.fee_exempt_accounts.insert(&account_id)
+        } else {
+            self.fee_exempt_accounts.remove(&account_id)
+        };
+        require!(changed, "Fee exemption is not changed.");
+
+        // 打印事件 log
+        FeeExemptionUpdate {
+            account_id: &account_id,
+            exempt,
+        }
+        .emit();
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 计算转账应收取的手续费
+    pub(crate) fn internal_transfer_fee(
+        &self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+    ) -> Balance {
+        let treasury_id = match &self.treasury_id {
+            Some(treasury_id) if self.fee_bps > 0 => treasury_id,
+            _ => return 0,
+        };
+        if sender_id == treasury_id
+            || receiver_id == treasury_id
+            || self.fee_exempt_accounts.contains(sender_id)
+            || self.fee_exempt_accounts.contains(receiver_id)
+        {
+            return 0;
+        }
+
+        // 拆分计算 amount * fee_bps / FEE_DENOMINATOR, 避免 u128 溢出
+        let fee_bps = Balance::from(self.fee_bps);
+        amount / FEE_DENOMINATOR * fee_bps + amount % FEE_DENOMINATOR * fee_bps / FEE_DENOMINATOR
+    }
+
+    // 把手续费从发送者转入金库账户
+    pub(crate) fn internal_charge_fee(&mut self, sender_id: &AccountId, fee: Balance) {
+        if fee == 0 {
+            return;
+        }
+        let treasury_id = self.treasury_id.clone().unwrap();
+        if !self.tokens.accounts.contains_key(&treasury_id) {
+            self.tokens.internal_register_account(&treasury_id);
+        }
+        self.internal_transfer_unchecked(
+            sender_id,
+            &treasury_id,
+            fee,
+            Some("transfer fee".to_string()),
+        );
+
+        // 打印事件 log
+        TransferFeeCharge {
+            sender_id,
+            treasury_id: &treasury_id,
+            amount: &U128(fee),
+        }
+        .emit();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::fungible_token::resolver::FungibleTokenResolver;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{
+        serde_json, testing_env, AccountId, PromiseResult, RuntimeFeesConfig, VMConfig, ONE_YOCTO,
+    };
+
+    fn treasury() -> AccountId {
+        "treasury.near".parse().unwrap()
+    }
+
+    // 手续费率为 1%
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(alice()), None);
+        contract.set_transfer_fee(100);
+        contract.set_treasury(Some(treasury()));
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    #[test]
+    fn test_transfer_with_fee() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(900 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(alice()), U128(99 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(treasury()), U128(ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(1000 * ONE_TOKEN));
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"transfer_fee_charge""#)));
+    }
+
+    #[test]
+    fn test_transfer_fee_exempt() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.set_fee_exempt(alice(), true);
+
+        assert!(contract.is_fee_exempt(alice()));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(treasury()), U128(0));
+    }
+
+    #[test]
+    fn test_transfer_call_refund_with_fee() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_call(alice(), U128(100 * ONE_TOKEN), None, String::new());
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(99 * ONE_TOKEN));
+
+        // 接收合约退回全部收到的 99 FT, 手续费不退还
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(owner())
+                .predecessor_account_id(owner())
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(
+                serde_json::to_vec(&U128(99 * ONE_TOKEN)).unwrap()
+            )],
+        );
+
+        let used_amount = contract.ft_resolve_transfer(bob(), alice(), U128(99 * ONE_TOKEN));
+
+        assert_eq!(used_amount, U128(0));
+        assert_eq!(contract.ft_balance_of(bob()), U128(999 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(alice()), U128(0));
+        assert_eq!(contract.ft_balance_of(treasury()), U128(ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer fee can not exceed 1000 basis points.")]
+    fn test_set_transfer_fee_over_limit() {
+        let mut contract = setup();
+
+        contract.set_transfer_fee(1001);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only contract owner can call this method.")]
+    fn test_set_treasury_by_other_account() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .build());
+
+        contract.set_treasury(Some(bob()));
+    }
+}
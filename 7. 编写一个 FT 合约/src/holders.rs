@@ -0,0 +1,306 @@
+use crate::allowance::settle_storage_deposit;
+use crate::events::HolderBackfillComplete;
+use crate::{Contract, ContractExt, StorageKey};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{TreeMap, UnorderedMap};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::{env, near_bindgen, require, AccountId, Balance};
+
+// 每次最多查询的持有者数, 也是不指定 `limit` 时的默认值
+pub const MAX_HOLDERS_LIMIT: u64 = 100;
+
+// 持有者索引, `FungibleToken.accounts` 是 `LookupMap`, 无法遍历, 因此单独维护
+// 在账户余额变化和注册注销时更新. 迁移前已注册的账户可以由任何人通过 `backfill_holders` 补全,
+// 已索引的余额之和等于总供应量时自动完成补全, 补全完成之前不能查询持有者
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct HolderIndex {
+    // 已注册的账户及其余额
+    balances: UnorderedMap<AccountId, Balance>,
+    // 余额大于 0 的账户, 按余额排序
+    ranking: TreeMap<(Balance, AccountId), ()>,
+    // 已索引账户的余额之和
+    supply: Balance,
+}
+
+impl HolderIndex {
+    pub fn new() -> Self {
+        Self {
+            balances: UnorderedMap::new(StorageKey::HolderBalances),
+            ranking: TreeMap::new(StorageKey::HolderRanking),
+            supply: 0,
+        }
+    }
+
+    // 更新账户的余额, `None` 表示账户已注销
+    fn update(&mut self, account_id: &AccountId, balance: Option<Balance>) {
+        let old_balance = self.balances.get(account_id);
+        if old_balance == balance {
+            return;
+        }
+        self.supply = self.supply - old_balance.unwrap_or(0) + balance.unwrap_or(0);
+        if let Some(old_balance) = old_balance.filter(|balance| *balance > 0) {
+            self.ranking.remove(&(old_balance, account_id.clone()));
+        }
+        match balance {
+            Some(balance) => {
+                self.balances.insert(account_id, &balance);
+                if balance > 0 {
+                    self.ranking.insert(&(balance, account_id.clone()), &());
+                }
+            }
+            None => {
+                self.balances.remove(account_id);
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Holder {
+    pub account_id: AccountId,
+    pub balance: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    // 把迁移前已注册的账户加入持有者索引, 账户由链下从历史交易中收集, 可以分多次调用. 未注册的账户会被忽略
+    // 索引的余额从链上读取, 因此任何人都可以调用, 新增的存储由调用者附加的 NEAR 支付, 多余部分退还
+    #[payable]
+    pub fn backfill_holders(&mut self, account_ids: Vec<AccountId>) {
+        let initial_storage_usage = env::storage_usage();
+        for account_id in account_ids.iter() {
+            self.internal_index_holder(account_id);
+        }
+        settle_storage_deposit(initial_storage_usage, &env::predecessor_account_id());
+
+        // 余额大于 0 的账户都已索引
+        if !self.holders_backfilled && self.holders.supply == self.tokens.total_supply {
+            self.internal_complete_holder_backfill();
+        }
+    }
+
+    // 合约所有者确认所有迁移前已注册的账户都已补全, 之后可以查询持有者
+    pub fn complete_holder_backfill(&mut self) {
+        self.assert_owner();
+        require!(
+            !self.holders_backfilled,
+            "The holder index has already been backfilled."
+        );
+        self.internal_complete_holder_backfill();
+    }
+
+    pub fn holders_backfilled(&self) -> bool {
+        self.holders_backfilled
+    }
+
+    // 分页查询已注册的账户及其余额, 顺序与注册顺序无关, 账户注销后顺序会改变
+    pub fn ft_holders(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<Holder> {
+        self.assert_holders_backfilled();
+        let account_ids = self.holders.balances.keys_as_vector();
+        let balances = self.holders.balances.values_as_vector();
+        let from_index = from_index.map_or(0, |index| index.0);
+        let end = from_index
+            .saturating_add(holders_limit(limit))
+            .min(account_ids.len());
+        (from_index..end)
+            .map(|index| Holder {
+                account_id: account_ids.get(index).unwrap(),
+                balance: U128(balances.get(index).unwrap()),
+            })
+            .collect()
+    }
+
+    // 查询已注册的账户数
+    pub fn ft_holder_count(&self) -> U64 {
+        self.assert_holders_backfilled();
+        U64(self.holders.balances.len())
+    }
+
+    // 按余额从高到低查询持有者, 只包含余额大于 0 的账户
+    pub fn ft_top_holders(&self, limit: Option<U64>) -> Vec<Holder> {
+        self.assert_holders_backfilled();
+        self.holders
+            .ranking
+            .iter_rev()
+            .take(holders_limit(limit) as usize)
+            .map(|((balance, account_id), _)| Holder {
+                account_id,
+                balance: U128(balance),
+            })
+            .collect()
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    fn assert_holders_backfilled(&self) {
+        require!(
+            self.holders_backfilled,
+            "The holder index is not backfilled yet."
+        );
+    }
+
+    fn internal_complete_holder_backfill(&mut self) {
+        self.holders_backfilled = true;
+
+        // 打印事件 log
+        HolderBackfillComplete {
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // 账户注册, 注销或余额变化之后调用
+    pub(crate) fn internal_index_holder(&mut self, account_id: &AccountId) {
+        let balance = self.tokens.accounts.get(account_id);
+        self.holders.update(account_id, balance);
+    }
+}
+
+// 不指定 `limit` 时默认查询 `MAX_HOLDERS_LIMIT` 个, 最多也只能查询这么多
+fn holders_limit(limit: Option<U64>) -> u64 {
+    limit.map_or(MAX_HOLDERS_LIMIT, |limit| limit.0.min(MAX_HOLDERS_LIMIT))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::holders::{Holder, MAX_HOLDERS_LIMIT};
+    use crate::test::{alice, bob, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, AccountId, ONE_YOCTO};
+
+    fn carol() -> AccountId {
+        "carol.near".parse().unwrap()
+    }
+
+    fn holder(account_id: AccountId, balance: u128) -> Holder {
+        Holder {
+            account_id,
+            balance: U128(balance),
+        }
+    }
+
+    // Bob 持有 1000 FT, Alice 持有 300 FT, Carol 已注册但没有余额
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.mint(alice(), U128(300 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(carol()), None);
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    #[test]
+    fn test_holders() {
+        let contract = setup();
+
+        assert_eq!(contract.ft_holder_count(), U64(3));
+        assert_eq!(
+            contract.ft_holders(None, None),
+            vec![
+                holder(bob(), 1000 * ONE_TOKEN),
+                holder(alice(), 300 * ONE_TOKEN),
+                holder(carol(), 0),
+            ]
+        );
+        assert_eq!(
+            contract.ft_holders(Some(U64(1)), Some(U64(1))),
+            vec![holder(alice(), 300 * ONE_TOKEN)]
+        );
+    }
+
+    #[test]
+    fn test_top_holders_after_transfer_and_burn() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(carol(), U128(500 * ONE_TOKEN), None);
+        contract.ft_burn(U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(
+            contract.ft_top_holders(Some(U64(10))),
+            vec![
+                holder(carol(), 500 * ONE_TOKEN),
+                holder(bob(), 400 * ONE_TOKEN),
+                holder(alice(), 300 * ONE_TOKEN),
+            ]
+        );
+        assert_eq!(
+            contract.ft_top_holders(Some(U64(1))),
+            vec![holder(carol(), 500 * ONE_TOKEN)]
+        );
+    }
+
+    #[test]
+    fn test_unregister_holder() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.storage_unregister(Some(true));
+
+        assert_eq!(contract.ft_holder_count(), U64(2));
+        assert_eq!(
+            contract.ft_top_holders(Some(U64(10))),
+            vec![holder(bob(), 1000 * ONE_TOKEN)]
+        );
+    }
+
+    #[test]
+    fn test_holders_limit() {
+        let mut contract = setup();
+
+        for index in 0..MAX_HOLDERS_LIMIT {
+            testing_env!(VMContextBuilder::new()
+                .predecessor_account_id(owner())
+                .build());
+
+            let account_id: AccountId = format!("holder{}.near", index).parse().unwrap();
+            contract.mint(account_id, U128(ONE_TOKEN), None);
+        }
+
+        assert_eq!(contract.ft_holder_count(), U64(MAX_HOLDERS_LIMIT + 3));
+        assert_eq!(
+            contract.ft_holders(None, None).len() as u64,
+            MAX_HOLDERS_LIMIT
+        );
+        assert_eq!(
+            contract.ft_holders(None, Some(U64(u64::MAX))).len() as u64,
+            MAX_HOLDERS_LIMIT
+        );
+        assert_eq!(
+            contract
+                .ft_holders(Some(U64(MAX_HOLDERS_LIMIT)), None)
+                .len(),
+            3
+        );
+        assert_eq!(
+            contract.ft_top_holders(Some(U64(u64::MAX))).len() as u64,
+            MAX_HOLDERS_LIMIT
+        );
+        assert_eq!(
+            contract.ft_top_holders(None).len() as u64,
+            MAX_HOLDERS_LIMIT
+        );
+    }
+}
@@ -1,104 +1,582 @@
-use near_contract_standards::fungible_token::events::{FtBurn, FtMint};
+mod allowance;
+mod batch;
+mod checkpoint;
+mod compliance;
+mod dividends;
+mod events;
+mod fee;
+mod holders;
+mod metadata;
+mod migrate;
+mod ownership;
+mod pause;
+mod permit;
+mod quota;
+mod roles;
+mod sponsor;
+mod staking;
+mod storage;
+mod supply;
+mod u256;
+mod upgrade;
+mod vesting;
+mod wrap;
+
+use crate::checkpoint::Checkpoint;
+use crate::dividends::DividendPool;
+use crate::events::{MaxSupplyUpdate, MetadataUpdate, OwnershipTransfer, RoleGrant};
+use crate::holders::HolderIndex;
+use crate::metadata::assert_valid_metadata;
+use crate::migrate::{StateVersion, CURRENT_STATE_VERSION};
+use crate::pause::{Feature, PausedFeatures};
+use crate::quota::{EpochMinted, RollingMintQuota};
+use crate::roles::{Role, RoleSet, OWNER_ROLES};
+use crate::sponsor::RegistrationSponsor;
+use crate::staking::StakingPool;
+use crate::storage::AccountStorage;
+use crate::vesting::VestingSchedule;
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_contract_standards::fungible_token::events::{FtBurn, FtMint, FtTransfer};
 use near_contract_standards::fungible_token::metadata::{
-    FungibleTokenMetadata, FungibleTokenMetadataProvider, FT_METADATA_SPEC,
+    FungibleTokenMetadata, FungibleTokenMetadataProvider,
 };
+use near_contract_standards::fungible_token::receiver::ext_ft_receiver;
+use near_contract_standards::fungible_token::resolver::{ext_ft_resolver, FungibleTokenResolver};
 use near_contract_standards::fungible_token::FungibleToken;
-use near_contract_standards::{impl_fungible_token_core, impl_fungible_token_storage};
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::json_types::U128;
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedSet, Vector};
+use near_sdk::json_types::{U128, U64};
 use near_sdk::{
-    env, near_bindgen, require, AccountId, Balance, BorshStorageKey, PanicOnDefault, PromiseOrValue,
+    assert_one_yocto, env, near_bindgen, require, AccountId, Balance, BorshStorageKey, CryptoHash,
+    Gas, PanicOnDefault, PromiseOrValue, PublicKey, StorageUsage,
 };
 
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000);
+// 转账本身除余额外还会更新手续费, 历史记录, 持有者索引和分红, 比标准实现多预留 10 TGas
+const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas(35_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
-    owner_id: AccountId,
+    // 合约所有者, `None` 表示所有权已被放弃
+    owner_id: Option<AccountId>,
+    // 被提议的新所有者, 需要由其主动接受所有权
+    pending_owner_id: Option<AccountId>,
     tokens: FungibleToken,
+    // 账户预存的存储费, 以及注册时除 FT 余额之外写入的存储
+    account_storages: LookupMap<AccountId, AccountStorage>,
+    registration_storage_usage: StorageUsage,
+    metadata: LazyOption<FungibleTokenMetadata>,
+    // 元数据的版本, 每次修改元数据时加 1
+    metadata_version: u64,
+
+    // FT 供应量上限, `None` 表示没有上限
+    max_supply: Option<Balance>,
+
+    // 账户拥有的角色
+    roles: LookupMap<AccountId, RoleSet>,
+    // 每个角色下的账户, 用于按角色分页查询账户
+    role_accounts: LookupMap<Role, UnorderedSet<AccountId>>,
+
+    // 各功能的暂停状态
+    paused: PausedFeatures,
+    // 被冻结的账户
+    frozen_accounts: LookupSet<AccountId>,
+
+    // (owner_id, spender_id) -> 授权额度
+    allowances: LookupMap<(AccountId, AccountId), Balance>,
+    // (owner_id, spender_id) -> 授权记录的存储费支付者
+    allowance_payers: LookupMap<(AccountId, AccountId), AccountId>,
+
+    // 账户的线性释放计划
+    vestings: LookupMap<AccountId, VestingSchedule>,
+
+    // 账户的历史余额记录
+    account_checkpoints: LookupMap<AccountId, Vector<Checkpoint>>,
+    // 历史总供应量记录
+    total_supply_checkpoints: Vector<Checkpoint>,
+
+    // wNEAR 模式, 供应量完全由存入的 NEAR 支撑, 不能通过 minter 凭空 mint
+    wrapped_near: bool,
+
+    // 转账手续费率, 单位为万分之一
+    fee_bps: u16,
+    // 收取手续费的金库账户, `None` 表示不收取手续费
+    treasury_id: Option<AccountId>,
+    // 免手续费的账户
+    fee_exempt_accounts: LookupSet<AccountId>,
+
+    // 被封禁的账户, 不能发送, 接收和注册
+    blocked_accounts: LookupSet<AccountId>,
+    // 白名单模式, 开启后只有白名单中的账户能发送, 接收和注册
+    allowlist_enabled: bool,
+    allowed_accounts: LookupSet<AccountId>,
+
+    // 由合约代付存储费的注册
+    sponsor: RegistrationSponsor,
+
+    // 签名授权用的 ed25519 key
+    permit_keys: LookupMap<AccountId, PublicKey>,
+    // 签名授权的 nonce, 每使用一次加一
+    permit_nonces: LookupMap<AccountId, u64>,
+
+    // minter 在滚动窗口期内的 mint 额度
+    mint_quotas: LookupMap<AccountId, RollingMintQuota>,
+    // 每个 epoch 内所有 minter 合计能 mint 的上限, `None` 表示没有上限
+    epoch_mint_cap: Option<Balance>,
+    epoch_minted: EpochMinted,
+
+    // 持有者索引, 以及迁移前已注册的账户是否已加入索引
+    holders: HolderIndex,
+    holders_backfilled: bool,
+    dividends: DividendPool,
+    staking: StakingPool,
+
+    // 合约状态的版本, 用于升级时迁移状态
+    state_version: StateVersion,
+    // 最近一次通过 `upgrade` 部署的代码哈希
+    code_hash: Option<CryptoHash>,
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
-enum StorageKey {
+pub(crate) enum StorageKey {
     FungibleToken,
+    AccountStorages,
+    Metadata,
+    Roles,
+    RoleAccounts,
+    RoleMembers { role: Role },
+    FrozenAccounts,
+    Allowances,
+    AllowancePayers,
+    Vestings,
+    Checkpoints,
+    AccountCheckpoints { account_hash: Vec<u8> },
+    TotalSupplyCheckpoints,
+    FeeExemptAccounts,
+    BlockedAccounts,
+    AllowedAccounts,
+    SponsorUsages,
+    SponsoredAccounts,
+    PermitKeys,
+    PermitNonces,
+    MintQuotas,
+    HolderBalances,
+    HolderRanking,
+    DividendAccounts,
+    Stakers,
 }
 
 #[near_bindgen]
 impl Contract {
+    // `initial_supply` 会在初始化时 mint 给合约所有者, 便于工厂合约一次性创建并发行 FT
     #[init]
-    pub fn init(owner_id: AccountId) -> Self {
-        Self {
-            owner_id,
-            tokens: FungibleToken::new(StorageKey::FungibleToken),
+    pub fn init(
+        owner_id: AccountId,
+        metadata: FungibleTokenMetadata,
+        max_supply: Option<U128>,
+        initial_supply: Option<U128>,
+    ) -> Self {
+        assert_valid_metadata(&metadata);
+        let mut this = Self::new(
+            owner_id.clone(),
+            &metadata,
+            max_supply.map(|max_supply| max_supply.0),
+            FungibleToken::new(StorageKey::FungibleToken),
+        );
+        this.internal_measure_registration_storage_usage();
+
+        // 打印事件 log, 初始化时的管理状态也通过事件记录, 链下服务无需单独读取初始状态
+        OwnershipTransfer {
+            old_owner_id: None,
+            new_owner_id: Some(&owner_id),
+        }
+        .emit();
+        MetadataUpdate {
+            metadata: &metadata,
+            version: U64(0),
         }
+        .emit();
+        if let Some(max_supply) = max_supply {
+            MaxSupplyUpdate {
+                old_max_supply: None,
+                new_max_supply: &max_supply,
+            }
+            .emit();
+        }
+
+        // 合约所有者初始拥有所有角色
+        for role in OWNER_ROLES {
+            this.internal_grant_role(&owner_id, role);
+
+            // 打印事件 log
+            RoleGrant {
+                account_id: &owner_id,
+                role,
+                by: &env::predecessor_account_id(),
+            }
+            .emit();
+        }
+
+        if let Some(initial_supply) = initial_supply.filter(|supply| supply.0 > 0) {
+            this.internal_mint(&owner_id, initial_supply.0, None);
+        }
+
+        this
     }
 
-    // 合约所有者能为任意用户 mint 指定数量的 FT
+    // minter 能为任意用户 mint 指定数量的 FT
     pub fn mint(&mut self, account_id: AccountId, amount: U128, memo: Option<String>) {
-        require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only contract owner can call this method."
-        );
+        self.assert_role(Role::Minter);
+        self.assert_not_wrapped_near();
+        self.internal_use_mint_quota(&env::predecessor_account_id(), amount.0);
         self.internal_mint(&account_id, amount.0, memo);
     }
 
-    // 合约所有者能为任意用户 burn 指定数量的 FT
+    // burner 能为任意用户 burn 指定数量的 FT
     pub fn burn(&mut self, account_id: AccountId, amount: U128, memo: Option<String>) {
-        require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only contract owner can call this method."
-        );
+        self.assert_role(Role::Burner);
+        self.assert_burner_allowed();
         self.internal_burn(&account_id, amount.0, memo);
     }
+
+    // 用户 burn 自己持有的 FT. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn ft_burn(&mut self, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        self.assert_not_frozen(&account_id);
+        self.assert_unlocked(&account_id, amount.0);
+        let initial_storage_usage = env::storage_usage();
+        self.internal_burn(&account_id, amount.0, memo);
+        self.internal_charge_storage(&account_id, initial_storage_usage);
+    }
+
+    // 被授权的用户消耗授权额度 burn `owner_id` 持有的 FT. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn ft_burn_from(&mut self, owner_id: AccountId, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        let spender_id = env::predecessor_account_id();
+        self.assert_not_frozen(&owner_id);
+        self.assert_not_frozen(&spender_id);
+        self.assert_compliant(&spender_id);
+        self.assert_unlocked(&owner_id, amount.0);
+        self.internal_spend_allowance(&owner_id, &spender_id, amount.0);
+        let initial_storage_usage = env::storage_usage();
+        self.internal_burn(&owner_id, amount.0, memo);
+        self.internal_charge_storage(&spender_id, initial_storage_usage);
+    }
 }
 
 // 为合约实现 NEP141
-// ft_transfer
-// ft_transfer_call
-// ft_total_supply
-// ft_balance_of
-// ft_resolve_transfer
-impl_fungible_token_core!(Contract, tokens);
+// 不使用 `impl_fungible_token_core!`, 以便所有转账都经过 `Contract::internal_transfer` 的检查
+#[near_bindgen]
+impl FungibleTokenCore for Contract {
+    #[payable]
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0, memo);
+    }
+
+    #[payable]
+    fn ft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_one_yocto();
+        require!(
+            env::prepaid_gas() > GAS_FOR_FT_TRANSFER_CALL,
+            "More gas is required"
+        );
+        let sender_id = env::predecessor_account_id();
+        let amount = U128(self.internal_transfer(&sender_id, &receiver_id, amount.0, memo));
+
+        // 调用接收合约的 `ft_on_transfer`, 再回调自己的 `ft_resolve_transfer` 处理退款
+        // 接收合约收到的是扣除手续费后的数量, 退款不退还手续费
+        let receiver_gas = env::prepaid_gas() - GAS_FOR_FT_TRANSFER_CALL;
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(receiver_gas)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                ext_ft_resolver::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount),
+            )
+            .into()
+    }
+
+    fn ft_total_supply(&self) -> U128 {
+        self.tokens.ft_total_supply()
+    }
+
+    fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        self.tokens.ft_balance_of(account_id)
+    }
+}
+
+// `ft_transfer_call` 的回调, 退款不受暂停和冻结状态影响, 退款新增的存储由发送者支付
+#[near_bindgen]
+impl FungibleTokenResolver for Contract {
+    #[private]
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        let initial_storage_usage = env::storage_usage();
+        self.internal_before_balance_change(&sender_id);
+        self.internal_before_balance_change(&receiver_id);
+        let (used_amount, burned_amount) =
+            self.tokens
+                .internal_ft_resolve_transfer(&sender_id, receiver_id.clone(), amount);
+
+        // 退款会改变双方余额, 发送者已注销时退款会被 burn
+        self.internal_on_balance_change(&sender_id);
+        self.internal_on_balance_change(&receiver_id);
+        if burned_amount > 0 {
+            self.internal_on_total_supply_change();
+        }
+        // 回调不能失败, 发送者可用余额不足的部分由合约承担
+        self.internal_charge_available_storage(&sender_id, initial_storage_usage);
+
+        used_amount.into()
+    }
+}
 
 // 为合约实现 NEP145
-// storage_deposit
-// storage_withdraw
-// storage_unregister
-// storage_balance_bounds
-// storage_balance_of
-impl_fungible_token_storage!(Contract, tokens);
+// 不使用 `impl_fungible_token_storage!`, 以便注册账户时检查封禁和白名单, 并记录账户实际支付的存储费
+#[near_bindgen]
+impl StorageManagement for Contract {
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        self.assert_compliant(&account_id);
+        self.internal_storage_deposit(&account_id, registration_only.unwrap_or(false));
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let storage_balance = self.internal_storage_balance_of(&account_id);
+        require!(
+            storage_balance.is_some(),
+            format!("The account {} is not registered", &account_id)
+        );
+        self.internal_storage_withdraw(&account_id, amount.map(|amount| amount.0));
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        if let Some(unregistered) = self.internal_sponsored_unregister(force) {
+            return unregistered;
+        }
+        self.internal_before_balance_change(&env::predecessor_account_id());
+        if let Some((account_id, balance)) = self.internal_storage_unregister(force) {
+            self.on_account_closed(account_id, balance);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        self.internal_storage_balance_bounds()
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(&account_id)
+    }
+}
 
 // 为合约实现 NEP148
 #[near_bindgen]
 impl FungibleTokenMetadataProvider for Contract {
     fn ft_metadata(&self) -> FungibleTokenMetadata {
-        FungibleTokenMetadata {
-            spec: FT_METADATA_SPEC.to_string(),
-            name: "Hello Fungible Token".to_string(),
-            symbol: "HelloFT".to_string(),
-            icon: None,
-            reference: None,
-            reference_hash: None,
-            decimals: 18,
-        }
+        self.metadata.get().unwrap()
     }
 }
 
 // ------------------------------------- 合约内部方法 ------------------------------------------------
 
 impl Contract {
-    pub(crate) fn internal_mint(
+    // 创建合约状态, 不打印事件 log, 由 `init` 和 `migrate` 调用
+    pub(crate) fn new(
+        owner_id: AccountId,
+        metadata: &FungibleTokenMetadata,
+        max_supply: Option<Balance>,
+        tokens: FungibleToken,
+    ) -> Self {
+        Self {
+            owner_id: Some(owner_id),
+            pending_owner_id: None,
+            tokens,
+            account_storages: LookupMap::new(StorageKey::AccountStorages),
+            registration_storage_usage: 0,
+            metadata: LazyOption::new(StorageKey::Metadata, Some(metadata)),
+            metadata_version: 0,
+            max_supply,
+            roles: LookupMap::new(StorageKey::Roles),
+            role_accounts: LookupMap::new(StorageKey::RoleAccounts),
+            paused: PausedFeatures::default(),
+            frozen_accounts: LookupSet::new(StorageKey::FrozenAccounts),
+            allowances: LookupMap::new(StorageKey::Allowances),
+            allowance_payers: LookupMap::new(StorageKey::AllowancePayers),
+            vestings: LookupMap::new(StorageKey::Vestings),
+            account_checkpoints: LookupMap::new(StorageKey::Checkpoints),
+            total_supply_checkpoints: Vector::new(StorageKey::TotalSupplyCheckpoints),
+            wrapped_near: false,
+            fee_bps: 0,
+            treasury_id: None,
+            fee_exempt_accounts: LookupSet::new(StorageKey::FeeExemptAccounts),
+            blocked_accounts: LookupSet::new(StorageKey::BlockedAccounts),
+            allowlist_enabled: false,
+            allowed_accounts: LookupSet::new(StorageKey::AllowedAccounts),
+            sponsor: RegistrationSponsor::new(),
+            permit_keys: LookupMap::new(StorageKey::PermitKeys),
+            permit_nonces: LookupMap::new(StorageKey::PermitNonces),
+            mint_quotas: LookupMap::new(StorageKey::MintQuotas),
+            epoch_mint_cap: None,
+            epoch_minted: EpochMinted::default(),
+            holders: HolderIndex::new(),
+            holders_backfilled: true,
+            dividends: DividendPool::new(),
+            staking: StakingPool::new(),
+            state_version: CURRENT_STATE_VERSION,
+            code_hash: None,
+        }
+    }
+
+    pub(crate) fn assert_owner(&self) {
+        require!(
+            self.owner_id.as_ref() == Some(&env::predecessor_account_id()),
+            "Only contract owner can call this method."
+        );
+    }
+
+    pub(crate) fn assert_transferable(&self, sender_id: &AccountId, receiver_id: &AccountId) {
+        self.assert_not_paused(Feature::Transfer);
+        self.assert_not_frozen(sender_id);
+        self.assert_not_frozen(receiver_id);
+        self.assert_compliant(sender_id);
+        self.assert_compliant(receiver_id);
+    }
+
+    // 账户余额发生变化之前调用
+    pub(crate) fn internal_before_balance_change(&mut self, account_id: &AccountId) {
+        self.internal_settle_dividends(account_id);
+        self.internal_remove_vested_schedule(account_id);
+    }
+
+    // 账户余额发生变化之后调用
+    pub(crate) fn internal_on_balance_change(&mut self, account_id: &AccountId) {
+        self.internal_checkpoint_balance(account_id);
+        self.internal_index_holder(account_id);
+    }
+
+    // 总供应量发生变化之后调用
+    pub(crate) fn internal_on_total_supply_change(&mut self) {
+        self.internal_checkpoint_total_supply();
+    }
+
+    // 用户强制注销持有者信息时, 其余额会被 burn, 已领完的分红记录随之删除
+    fn on_account_closed(&mut self, account_id: AccountId, balance: Balance) {
+        self.internal_on_balance_change(&account_id);
+        self.internal_remove_settled_dividends(&account_id);
+        if balance > 0 {
+            self.internal_on_total_supply_change();
+        }
+    }
+
+    // 所有用户发起的转账都要经过该方法, 返回接收者实际收到的数量
+    pub(crate) fn internal_transfer(
         &mut self,
-        account_id: &AccountId,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+        memo: Option<String>,
+    ) -> Balance {
+        // 检查暂停, 冻结, 合规和锁仓状态
+        self.assert_transferable(sender_id, receiver_id);
+        self.assert_unlocked(sender_id, amount);
+
+        // 接收者未注册时尝试由合约代付存储费
+        self.internal_sponsor_registration(sender_id, receiver_id);
+
+        // 手续费从转账数量中扣除, 转账新增的存储由调用者支付
+        let initial_storage_usage = env::storage_usage();
+        let fee = self.internal_transfer_fee(sender_id, receiver_id, amount);
+        self.internal_transfer_unchecked(sender_id, receiver_id, amount - fee, memo);
+        self.internal_charge_fee(sender_id, fee);
+        self.internal_charge_storage(&env::predecessor_account_id(), initial_storage_usage);
+        amount - fee
+    }
+
+    // 不做任何检查的转账, 仅用于管理操作
+    pub(crate) fn internal_transfer_unchecked(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
         amount: Balance,
         memo: Option<String>,
     ) {
-        // 注册 FT 持有者信息
-        if !self.tokens.accounts.contains_key(account_id) {
-            self.tokens.internal_register_account(account_id);
+        self.internal_move(sender_id, receiver_id, amount);
+
+        // 打印标准 log
+        FtTransfer {
+            old_owner_id: sender_id,
+            new_owner_id: receiver_id,
+            amount: &U128(amount),
+            memo: memo.as_deref(),
         }
+        .emit();
+    }
 
-        // mint
-        self.tokens.internal_deposit(account_id, amount);
+    // 只移动余额, 不打印 log, 由调用者负责打印
+    pub(crate) fn internal_move(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+    ) {
+        require!(
+            sender_id != receiver_id,
+            "Sender and receiver should be different"
+        );
+        require!(amount > 0, "The amount should be a positive number");
+        require!(
+            self.tokens.accounts.contains_key(receiver_id),
+            format!("The account {} is not registered", receiver_id)
+        );
+        self.internal_before_balance_change(sender_id);
+        self.internal_before_balance_change(receiver_id);
+        self.tokens.internal_withdraw(sender_id, amount);
+        self.tokens.internal_deposit(receiver_id, amount);
+        self.internal_on_balance_change(sender_id);
+        self.internal_on_balance_change(receiver_id);
+    }
+
+    pub(crate) fn internal_mint(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance,
+        memo: Option<String>,
+    ) {
+        self.internal_mint_without_event(account_id, amount);
 
         // 打印标准 log
         FtMint {
@@ -109,14 +587,42 @@ impl Contract {
         .emit();
     }
 
+    // mint 但不打印 log, 由调用者负责打印
+    pub(crate) fn internal_mint_without_event(&mut self, account_id: &AccountId, amount: Balance) {
+        // 检查暂停, 冻结和合规状态
+        self.assert_not_paused(Feature::Mint);
+        self.assert_not_frozen(account_id);
+        self.assert_compliant(account_id);
+
+        // 检查供应量上限
+        self.assert_mintable(amount);
+
+        // 注册 FT 持有者信息
+        if !self.tokens.accounts.contains_key(account_id) {
+            self.tokens.internal_register_account(account_id);
+        }
+
+        // mint
+        self.internal_before_balance_change(account_id);
+        self.tokens.internal_deposit(account_id, amount);
+        self.internal_on_balance_change(account_id);
+        self.internal_on_total_supply_change();
+    }
+
     pub(crate) fn internal_burn(
         &mut self,
         account_id: &AccountId,
         amount: Balance,
         memo: Option<String>,
     ) {
+        // 检查暂停状态
+        self.assert_not_paused(Feature::Burn);
+
         // burn
+        self.internal_before_balance_change(account_id);
         self.tokens.internal_withdraw(account_id, amount);
+        self.internal_on_balance_change(account_id);
+        self.internal_on_total_supply_change();
 
         // 打印标准 log
         FtBurn {
@@ -129,31 +635,90 @@ impl Contract {
 }
 
 #[cfg(test)]
-mod test {
-    use crate::Contract;
+pub(crate) mod test {
+    use crate::{Contract, GAS_FOR_FT_TRANSFER_CALL, GAS_FOR_RESOLVE_TRANSFER};
     use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::fungible_token::metadata::{
+        FungibleTokenMetadata, FT_METADATA_SPEC,
+    };
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+    use near_contract_standards::fungible_token::resolver::FungibleTokenResolver;
     use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::borsh::BorshSerialize;
     use near_sdk::json_types::U128;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env, AccountId, Balance, ONE_YOCTO};
-
-    fn owner() -> AccountId {
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{
+        env, serde_json, testing_env, AccountId, Balance, Gas, PromiseOrValue, PromiseResult,
+        RuntimeFeesConfig, VMConfig, ONE_NEAR, ONE_YOCTO,
+    };
+
+    pub(crate) fn owner() -> AccountId {
         "owner.near".parse().unwrap()
     }
 
-    fn alice() -> AccountId {
+    pub(crate) fn alice() -> AccountId {
         "alice.near".parse().unwrap()
     }
 
-    fn bob() -> AccountId {
+    pub(crate) fn bob() -> AccountId {
         "bob.near".parse().unwrap()
     }
 
-    const ONE_TOKEN: Balance = 1_000_000_000_000_000_000;
+    pub(crate) fn metadata() -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "Hello Fungible Token".to_string(),
+            symbol: "HelloFT".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: 18,
+        }
+    }
+
+    pub(crate) const ONE_TOKEN: Balance = 1_000_000_000_000_000_000;
+
+    // 给 `account_id` 预存 1 NEAR 的存储费, 用于支付其发起的调用新增的存储. 调用前后的其余上下文保持不变
+    pub(crate) fn deposit_storage(contract: &mut Contract, account_id: AccountId) {
+        let context = |predecessor_id: AccountId, attached_deposit: Balance| {
+            VMContextBuilder::new()
+                .predecessor_account_id(predecessor_id)
+                .current_account_id(env::current_account_id())
+                .attached_deposit(attached_deposit)
+                .block_index(env::block_height())
+                .block_timestamp(env::block_timestamp())
+                .storage_usage(env::storage_usage())
+                .build()
+        };
+        let predecessor_id = env::predecessor_account_id();
+        let attached_deposit = env::attached_deposit();
+
+        testing_env!(context(account_id.clone(), ONE_NEAR));
+        contract.storage_deposit(Some(account_id), None);
+        testing_env!(context(predecessor_id, attached_deposit));
+    }
+
+    // 工厂合约按该用量收取新 FT 的存储费 (`TOKEN_STATE_BYTES`), 初始化写入的状态变多时需要同步修改
+    #[test]
+    fn test_init_storage_usage() {
+        let owner_id = AccountId::new_unchecked("a".repeat(64));
+        let initial_storage_usage = env::storage_usage();
+        let contract = Contract::init(
+            owner_id,
+            metadata(),
+            Some(U128(Balance::MAX)),
+            Some(U128(ONE_TOKEN)),
+        );
+        env::state_write(&contract);
+
+        let metadata_len = metadata().try_to_vec().unwrap().len() as u64;
+        assert!(env::storage_usage() - initial_storage_usage - metadata_len <= 3_000);
+    }
 
     #[test]
     fn test_mint_transfer_burn() {
-        let mut contract = Contract::init(owner());
+        let mut contract = Contract::init(owner(), metadata(), None, None);
 
         // ----------------------------- 给 Bob mint 1000 FT ---------------------------------------
 
@@ -178,6 +743,9 @@ mod test {
 
         contract.storage_deposit(Some(alice()), None);
 
+        // 转账新增的存储由 Bob 预存的存储费支付
+        deposit_storage(&mut contract, bob());
+
         // `ft_transfer` 调用需要附加 1 yocto NEAR
         testing_env!(VMContextBuilder::new()
             .predecessor_account_id(bob())
@@ -202,4 +770,239 @@ mod test {
         assert_eq!(contract.ft_balance_of(alice()), U128(200 * ONE_TOKEN));
         assert_eq!(contract.ft_total_supply(), U128(700 * ONE_TOKEN));
     }
+
+    #[test]
+    fn test_ft_burn() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+
+        // ----------------------------- Bob burn 自己的 100 FT -------------------------------------
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_burn(U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(900 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(900 * ONE_TOKEN));
+        assert!(get_logs()[0].contains(r#""event":"ft_burn""#));
+
+        // ------------------------- Alice 消耗授权额度 burn Bob 的 50 FT ---------------------------
+
+        contract.internal_set_allowance(&bob(), &alice(), 80 * ONE_TOKEN);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_burn_from(bob(), U128(50 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(850 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(850 * ONE_TOKEN));
+        assert_eq!(
+            contract.internal_allowance(&bob(), &alice()),
+            30 * ONE_TOKEN
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient allowance.")]
+    fn test_ft_burn_from_over_allowance() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.internal_set_allowance(&bob(), &alice(), 80 * ONE_TOKEN);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_burn_from(bob(), U128(81 * ONE_TOKEN), None);
+    }
+
+    // ------------------------------------- ft_transfer_call ------------------------------------------
+
+    pub(crate) fn contract_id() -> AccountId {
+        "hello_ft.near".parse().unwrap()
+    }
+
+    fn vault() -> AccountId {
+        "vault.near".parse().unwrap()
+    }
+
+    // Bob 持有 1000 FT, 接收合约 vault.near 已注册
+    fn setup_transfer_call() -> Contract {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(owner())
+            .build());
+
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.storage_deposit(Some(vault()), None);
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    // 在独立线程中执行接收合约 `hello_ft_receiver`, 使其拥有独立的存储, 模拟另一个合约账户
+    // 接收合约 panic 时返回 `PromiseResult::Failed`
+    fn call_receiver(sender_id: AccountId, amount: U128, msg: &str) -> PromiseResult {
+        let msg = msg.to_string();
+        std::thread::spawn(move || {
+            testing_env!(VMContextBuilder::new()
+                .current_account_id(vault())
+                .predecessor_account_id(contract_id())
+                .build());
+
+            let mut receiver = hello_ft_receiver::Contract::init(contract_id());
+            match receiver.ft_on_transfer(sender_id, amount, msg) {
+                PromiseOrValue::Value(refund) => serde_json::to_vec(&refund).unwrap(),
+                PromiseOrValue::Promise(_) => unreachable!(),
+            }
+        })
+        .join()
+        .map_or(PromiseResult::Failed, PromiseResult::Successful)
+    }
+
+    // Bob 通过 `ft_transfer_call` 给 vault.near 转账 100 FT, 返回 `ft_on_transfer` 的执行结果
+    fn transfer_call(contract: &mut Contract, msg: &str) -> PromiseResult {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer_call(vault(), U128(100 * ONE_TOKEN), None, msg.to_string());
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(900 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(vault()), U128(100 * ONE_TOKEN));
+
+        call_receiver(bob(), U128(100 * ONE_TOKEN), msg)
+    }
+
+    fn resolve_transfer(contract: &mut Contract, result: PromiseResult) -> U128 {
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(contract_id())
+                .predecessor_account_id(contract_id())
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+
+        contract.ft_resolve_transfer(bob(), vault(), U128(100 * ONE_TOKEN))
+    }
+
+    #[test]
+    fn test_transfer_call_accept() {
+        let mut contract = setup_transfer_call();
+
+        let result = transfer_call(&mut contract, "");
+        let used_amount = resolve_transfer(&mut contract, result);
+
+        assert_eq!(used_amount, U128(100 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(900 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(vault()), U128(100 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_transfer_call_gas() {
+        let mut contract = setup_transfer_call();
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .prepaid_gas(Gas(100_000_000_000_000))
+            .build());
+
+        contract.ft_transfer_call(vault(), U128(100 * ONE_TOKEN), None, String::new());
+
+        // 接收合约得到预留之外的全部 gas, 回调使用固定的 gas
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, vault());
+        assert!(matches!(
+            &receipts[0].actions[..],
+            [VmAction::FunctionCall { function_name, gas, .. }]
+                if function_name == "ft_on_transfer"
+                    && *gas == Gas(100_000_000_000_000) - GAS_FOR_FT_TRANSFER_CALL
+        ));
+        assert!(matches!(
+            &receipts[1].actions[..],
+            [VmAction::FunctionCall { function_name, gas, .. }]
+                if function_name == "ft_resolve_transfer" && *gas == GAS_FOR_RESOLVE_TRANSFER
+        ));
+    }
+
+    #[test]
+    fn test_transfer_call_partial_refund() {
+        let mut contract = setup_transfer_call();
+
+        let result = transfer_call(&mut contract, &format!("refund:{}", 30 * ONE_TOKEN));
+        let used_amount = resolve_transfer(&mut contract, result);
+
+        assert_eq!(used_amount, U128(70 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(930 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(vault()), U128(70 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_transfer_call_receiver_panic() {
+        let mut contract = setup_transfer_call();
+
+        // 接收合约执行失败时全部退回
+        let result = transfer_call(&mut contract, "panic");
+        assert_eq!(result, PromiseResult::Failed);
+
+        let used_amount = resolve_transfer(&mut contract, result);
+
+        assert_eq!(used_amount, U128(0));
+        assert_eq!(contract.ft_balance_of(bob()), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(vault()), U128(0));
+    }
+
+    #[test]
+    fn test_transfer_call_receiver_burned() {
+        let mut contract = setup_transfer_call();
+
+        let result = transfer_call(&mut contract, "refund_all");
+
+        // 回调执行前接收合约已 burn 掉 60 FT, 只能退回剩余的 40 FT
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(vault())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_burn(U128(60 * ONE_TOKEN), None);
+
+        let used_amount = resolve_transfer(&mut contract, result);
+
+        assert_eq!(used_amount, U128(60 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(940 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(vault()), U128(0));
+        assert_eq!(contract.ft_total_supply(), U128(940 * ONE_TOKEN));
+    }
 }
@@ -0,0 +1,234 @@
+use crate::events::MetadataUpdate;
+use crate::roles::Role;
+use crate::{Contract, ContractExt};
+use near_contract_standards::fungible_token::metadata::{FungibleTokenMetadata, FT_METADATA_SPEC};
+use near_sdk::json_types::U64;
+use near_sdk::{near_bindgen, require};
+
+// FT 精度上限, 与 NEAR 本身的精度 (1 NEAR = 10^24 yocto NEAR) 保持一致
+pub const MAX_DECIMALS: u8 = 24;
+
+// 图标 data URI 的最大长度 (字节), 避免元数据占用过多存储, 也避免钱包加载过大的图标
+pub const MAX_ICON_LENGTH: usize = 16 * 1024;
+
+#[near_bindgen]
+impl Contract {
+    // metadata admin 能替换整个 FT 元数据, 但不能修改精度, 否则所有用户的余额含义都会改变
+    pub fn set_metadata(&mut self, metadata: FungibleTokenMetadata) {
+        self.assert_role(Role::MetadataAdmin);
+        let old_metadata = self.metadata.get().unwrap();
+        require!(
+            metadata.decimals == old_metadata.decimals,
+            "Decimals can not be changed."
+        );
+        self.internal_set_metadata(metadata);
+    }
+
+    // metadata admin 能单独修改 FT 图标, 传入 `None` 表示移除图标
+    pub fn set_icon(&mut self, icon: Option<String>) {
+        self.assert_role(Role::MetadataAdmin);
+        let mut metadata = self.metadata.get().unwrap();
+        metadata.icon = icon;
+        self.internal_set_metadata(metadata);
+    }
+
+    // 查询元数据的版本, 每次修改元数据时加 1, 便于链下服务判断缓存是否过期
+    pub fn ft_metadata_version(&self) -> U64 {
+        U64(self.metadata_version)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn internal_set_metadata(&mut self, metadata: FungibleTokenMetadata) {
+        assert_valid_metadata(&metadata);
+        self.metadata.set(&metadata);
+        self.metadata_version += 1;
+
+        // 打印事件 log
+        MetadataUpdate {
+            metadata: &metadata,
+            version: U64(self.metadata_version),
+        }
+        .emit();
+    }
+}
+
+pub(crate) fn assert_valid_metadata(metadata: &FungibleTokenMetadata) {
+    require!(
+        metadata.spec == FT_METADATA_SPEC,
+        format!("Metadata spec must be {}.", FT_METADATA_SPEC)
+    );
+    require!(!metadata.name.is_empty(), "Metadata name can not be empty.");
+    require!(
+        !metadata.symbol.is_empty(),
+        "Metadata symbol can not be empty."
+    );
+    require!(
+        metadata.decimals <= MAX_DECIMALS,
+        format!("Metadata decimals must not exceed {}.", MAX_DECIMALS)
+    );
+
+    // 图标必须是图片的 data URI, 如 `data:image/svg+xml,...`, 以保证图标数据上链, 不依赖外部服务
+    if let Some(icon) = &metadata.icon {
+        let is_image_data_uri = icon
+            .strip_prefix("data:image/")
+            .is_some_and(|data| data.contains(','));
+        require!(is_image_data_uri, "Metadata icon must be a data URI.");
+        require!(
+            icon.len() <= MAX_ICON_LENGTH,
+            format!("Metadata icon must not exceed {} bytes.", MAX_ICON_LENGTH)
+        );
+    }
+
+    // `reference` 和 `reference_hash` 必须同时提供或同时不提供
+    require!(
+        metadata.reference.is_some() == metadata.reference_hash.is_some(),
+        "Metadata reference and reference_hash must be both set or both unset."
+    );
+    // `reference` 指向链下的 JSON 文件, `reference_hash` 为其 sha256 哈希, 以 base64 编码传入
+    if let Some(reference) = &metadata.reference {
+        require!(
+            !reference.trim().is_empty(),
+            "Metadata reference can not be empty."
+        );
+    }
+    if let Some(reference_hash) = &metadata.reference_hash {
+        require!(
+            reference_hash.0.len() == 32,
+            "Metadata reference_hash must be 32 bytes."
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::MAX_ICON_LENGTH;
+    use crate::test::{alice, metadata, owner};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::metadata::FungibleTokenMetadataProvider;
+    use near_sdk::json_types::{Base64VecU8, U64};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_init_with_metadata() {
+        let contract = Contract::init(owner(), metadata(), None, None);
+        let ft_metadata = contract.ft_metadata();
+        assert_eq!(ft_metadata.name, "Hello Fungible Token");
+        assert_eq!(ft_metadata.symbol, "HelloFT");
+        assert_eq!(ft_metadata.decimals, 18);
+        assert_eq!(contract.ft_metadata_version(), U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata decimals must not exceed 24.")]
+    fn test_init_with_invalid_decimals() {
+        let mut metadata = metadata();
+        metadata.decimals = 25;
+        Contract::init(owner(), metadata, None, None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Metadata reference and reference_hash must be both set or both unset."
+    )]
+    fn test_init_with_unpaired_reference() {
+        let mut metadata = metadata();
+        metadata.reference = Some("https://example.com/hello_ft.json".to_string());
+        Contract::init(owner(), metadata, None, None);
+    }
+
+    #[test]
+    fn test_set_metadata_and_icon() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        let mut new_metadata = metadata();
+        new_metadata.name = "Hello Token".to_string();
+        new_metadata.reference = Some("https://example.com/hello_ft.json".to_string());
+        new_metadata.reference_hash = Some(Base64VecU8(vec![0; 32]));
+        contract.set_metadata(new_metadata);
+
+        assert_eq!(contract.ft_metadata().name, "Hello Token");
+        assert_eq!(contract.ft_metadata_version(), U64(1));
+        assert!(get_logs()[0].starts_with(
+            r#"EVENT_JSON:{"standard":"hello_ft","version":"1.0.0","event":"metadata_update""#
+        ));
+
+        contract.set_icon(Some("data:image/svg+xml,<svg></svg>".to_string()));
+
+        let ft_metadata = contract.ft_metadata();
+        assert_eq!(ft_metadata.name, "Hello Token");
+        assert_eq!(
+            ft_metadata.icon.as_deref(),
+            Some("data:image/svg+xml,<svg></svg>")
+        );
+        assert_eq!(contract.ft_metadata_version(), U64(2));
+        assert!(get_logs().last().unwrap().contains(r#""version":"2""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata reference_hash must be 32 bytes.")]
+    fn test_init_with_invalid_reference_hash() {
+        let mut metadata = metadata();
+        metadata.reference = Some("https://example.com/hello_ft.json".to_string());
+        metadata.reference_hash = Some(Base64VecU8(vec![0; 31]));
+        Contract::init(owner(), metadata, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata icon must not exceed 16384 bytes.")]
+    fn test_set_icon_over_size_limit() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        let icon = format!("data:image/svg+xml,{}", "0".repeat(MAX_ICON_LENGTH));
+        contract.set_icon(Some(icon));
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata icon must be a data URI.")]
+    fn test_set_icon_with_url() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.set_icon(Some("https://example.com/icon.png".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Decimals can not be changed.")]
+    fn test_set_metadata_with_new_decimals() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        let mut new_metadata = metadata();
+        new_metadata.decimals = 6;
+        contract.set_metadata(new_metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only metadata_admin can call this method.")]
+    fn test_set_icon_without_permission() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .build());
+
+        contract.set_icon(None);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::roles::OWNER_ROLES;
+use crate::{Contract, ContractExt};
+use near_contract_standards::fungible_token::metadata::{FungibleTokenMetadata, FT_METADATA_SPEC};
+use near_contract_standards::fungible_token::FungibleToken;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+// 合约状态的版本. 修改 `Contract` 的字段时需要增加新版本, 保留旧版本的结构, 并在 `migrate` 中处理
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StateVersion {
+    // 最初的版本, 只有合约所有者和 FT, 状态中没有版本字段
+    V0,
+    V1,
+}
+
+pub const CURRENT_STATE_VERSION: StateVersion = StateVersion::V1;
+
+// `StateVersion::V0` 的合约状态
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV0 {
+    pub owner_id: AccountId,
+    pub tokens: FungibleToken,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn state_version(&self) -> StateVersion {
+        self.state_version
+    }
+
+    // 部署新代码后调用, 把旧版本的合约状态迁移为当前版本, FT 余额和存储注册保持不变
+    // 状态已是当前版本时不做修改, 以便 `upgrade` 在每次部署后都能调用
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let state = env::storage_read(b"STATE");
+        require!(state.is_some(), "The contract is not initialized.");
+        let state = state.unwrap();
+
+        if let Ok(contract) = Contract::try_from_slice(&state) {
+            require!(
+                contract.state_version == CURRENT_STATE_VERSION,
+                "Unknown state version."
+            );
+            return contract;
+        }
+
+        match ContractV0::try_from_slice(&state) {
+            Ok(old_state) => Self::migrate_from_v0(old_state),
+            Err(_) => env::panic_str("Unknown state layout."),
+        }
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    fn migrate_from_v0(old_state: ContractV0) -> Self {
+        // V0 的元数据是写死在代码中的
+        let metadata = FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "Hello Fungible Token".to_string(),
+            symbol: "HelloFT".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: 18,
+        };
+
+        // V0 的 FT 使用相同的存储前缀, 直接沿用即可保留所有余额和存储注册, 单个账户占用的存储也保持不变
+        let owner_id = old_state.owner_id;
+        let mut this = Self::new(owner_id.clone(), &metadata, None, old_state.tokens);
+
+        // 迁移不是新部署, 不打印初始化的事件 log. 合约所有者继续拥有 V0 中 mint 和 burn 的权限
+        for role in OWNER_ROLES {
+            this.internal_grant_role(&owner_id, role);
+        }
+
+        // 迁移前已注册的账户不在持有者索引中, 需要补全
+        this.holders_backfilled = false;
+        this.internal_measure_registration_storage_usage();
+
+        // V0 没有历史记录, 从迁移时开始记录总供应量
+        this.internal_on_total_supply_change();
+        this
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::migrate::CURRENT_STATE_VERSION;
+    use crate::roles::Role;
+    use crate::test::{alice, bob, deposit_storage, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::fungible_token::metadata::FungibleTokenMetadataProvider;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{env, testing_env, ONE_NEAR, ONE_YOCTO};
+
+    // 按 borsh 格式编码的 `AccountId`
+    fn borsh_account_id(account_id: &str) -> Vec<u8> {
+        [
+            &(account_id.len() as u32).to_le_bytes(),
+            account_id.as_bytes(),
+        ]
+        .concat()
+    }
+
+    // 写入 V0 合约的原始状态: owner.near 为所有者, alice.near 持有 100 FT, bob.near 持有 50 FT
+    fn write_v0_state() {
+        // FT 持有者信息的前缀为 `StorageKey::FungibleToken`, 即 [0]
+        for (account_id, balance) in [
+            ("alice.near", 100 * ONE_TOKEN),
+            ("bob.near", 50 * ONE_TOKEN),
+        ] {
+            let key = [&[0], borsh_account_id(account_id).as_slice()].concat();
+            env::storage_write(&key, &balance.to_le_bytes());
+        }
+
+        // STATE: owner_id, tokens.accounts 的前缀, tokens.total_supply, tokens.account_storage_usage
+        let state = [
+            borsh_account_id("owner.near"),
+            vec![1, 0, 0, 0, 0],
+            (150 * ONE_TOKEN).to_le_bytes().to_vec(),
+            129u64.to_le_bytes().to_vec(),
+        ]
+        .concat();
+        env::storage_write(b"STATE", &state);
+    }
+
+    #[test]
+    fn test_migrate_from_v0() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        write_v0_state();
+        let mut contract = Contract::migrate();
+
+        // 迁移不打印初始化的事件 log, FT 余额占用的存储与 V0 相同
+        assert!(get_logs().is_empty());
+        assert_eq!(contract.tokens.account_storage_usage, 129);
+        assert_eq!(
+            contract.storage_balance_bounds().min.0,
+            (129 + contract.registration_storage_usage) as u128 * env::storage_byte_cost()
+        );
+        assert_eq!(contract.state_version(), CURRENT_STATE_VERSION);
+        assert_eq!(contract.owner_id(), Some(owner()));
+        assert!(contract.has_role(owner(), Role::Minter));
+        assert_eq!(contract.ft_metadata().symbol, "HelloFT");
+        assert_eq!(contract.ft_total_supply(), U128(150 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(50 * ONE_TOKEN));
+        assert!(contract.storage_balance_of(alice()).is_some());
+
+        // 迁移前注册的账户预存存储费后能正常转账
+        deposit_storage(&mut contract, alice());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(bob(), U128(30 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(70 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(80 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_backfill_holders_after_migration() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        write_v0_state();
+        let mut contract = Contract::migrate();
+        assert!(!contract.holders_backfilled());
+
+        // 任何人都可以补全, 未注册的账户会被忽略. 余额都已索引后自动完成补全
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR)
+            .build());
+
+        contract.backfill_holders(vec![alice()]);
+        assert!(!contract.holders_backfilled());
+
+        contract.backfill_holders(vec![bob(), "carol.near".parse().unwrap()]);
+        assert!(contract.holders_backfilled());
+        assert!(get_logs()[0].contains(r#""event":"holder_backfill_complete""#));
+
+        assert_eq!(contract.ft_holder_count(), U64(2));
+        assert_eq!(
+            contract
+                .ft_top_holders(Some(U64(10)))
+                .iter()
+                .map(|holder| holder.account_id.clone())
+                .collect::<Vec<_>>(),
+            vec![alice(), bob()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The holder index is not backfilled yet.")]
+    fn test_holders_before_backfill() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        write_v0_state();
+        let mut contract = Contract::migrate();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(ONE_NEAR)
+            .build());
+
+        contract.backfill_holders(vec![alice()]);
+
+        contract.ft_holder_count();
+    }
+
+    #[test]
+    fn test_migrate_twice() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        write_v0_state();
+        let contract = Contract::migrate();
+        env::state_write(&contract);
+
+        assert_eq!(contract.state_version(), CURRENT_STATE_VERSION);
+
+        // 状态已是当前版本, 再次迁移不做修改
+        let contract = Contract::migrate();
+
+        assert_eq!(contract.state_version(), CURRENT_STATE_VERSION);
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+    }
+}
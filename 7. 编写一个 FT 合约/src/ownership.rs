@@ -0,0 +1,299 @@
+use crate::events::{OwnershipProposal, OwnershipProposalCancel, OwnershipTransfer};
+use crate::{Contract, ContractExt};
+use near_sdk::{assert_one_yocto, env, near_bindgen, require, AccountId};
+
+// 合约所有权通过两步转移: 当前所有者提议新所有者, 新所有者主动接受
+// 避免因为填错账户导致合约失去所有者. 原所有者拥有的初始角色随所有权一起转移
+#[near_bindgen]
+impl Contract {
+    pub fn owner_id(&self) -> Option<AccountId> {
+        self.owner_id.clone()
+    }
+
+    pub fn pending_owner_id(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+
+    // 合约所有者提议新的所有者, 会覆盖之前的提议
+    pub fn propose_owner(&mut self, new_owner_id: AccountId) {
+        self.assert_owner();
+        require!(
+            self.owner_id.as_ref() != Some(&new_owner_id),
+            "The account is already the owner."
+        );
+        self.pending_owner_id = Some(new_owner_id.clone());
+
+        // 打印事件 log
+        OwnershipProposal {
+            owner_id: &env::predecessor_account_id(),
+            pending_owner_id: &new_owner_id,
+        }
+        .emit();
+    }
+
+    // 被提议的账户接受所有权. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn accept_ownership(&mut self) {
+        assert_one_yocto();
+        let new_owner_id = env::predecessor_account_id();
+        require!(
+            self.pending_owner_id.as_ref() == Some(&new_owner_id),
+            "Only pending owner can call this method."
+        );
+
+        let old_owner_id = self.owner_id.replace(new_owner_id.clone());
+        self.pending_owner_id = None;
+
+        // 打印事件 log
+        OwnershipTransfer {
+            old_owner_id: old_owner_id.as_ref(),
+            new_owner_id: Some(&new_owner_id),
+        }
+        .emit();
+
+        if let Some(old_owner_id) = old_owner_id {
+            self.internal_transfer_owner_roles(&old_owner_id, Some(&new_owner_id));
+        }
+    }
+
+    // 合约所有者撤回尚未被接受的提议
+    pub fn cancel_ownership_proposal(&mut self) {
+        self.assert_owner();
+        let pending_owner_id = self
+            .pending_owner_id
+            .take()
+            .unwrap_or_else(|| env::panic_str("There is no pending owner."));
+
+        // 打印事件 log
+        OwnershipProposalCancel {
+            owner_id: &env::predecessor_account_id(),
+            pending_owner_id: &pending_owner_id,
+        }
+        .emit();
+    }
+
+    // 合约所有者永久放弃所有权, 之后所有仅限所有者的方法都无法再被调用. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn renounce_ownership(&mut self) {
+        assert_one_yocto();
+        self.assert_owner();
+        let old_owner_id = self.owner_id.take();
+        self.pending_owner_id = None;
+
+        // 打印事件 log
+        OwnershipTransfer {
+            old_owner_id: old_owner_id.as_ref(),
+            new_owner_id: None,
+        }
+        .emit();
+
+        if let Some(old_owner_id) = old_owner_id {
+            self.internal_transfer_owner_roles(&old_owner_id, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::roles::{Role, OWNER_ROLES};
+    use crate::test::{alice, bob, metadata, owner};
+    use crate::Contract;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, ONE_YOCTO};
+
+    #[test]
+    fn test_propose_accept_ownership() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.propose_owner(alice());
+
+        assert_eq!(contract.owner_id(), Some(owner()));
+        assert_eq!(contract.pending_owner_id(), Some(alice()));
+        assert!(get_logs()[0].contains(r#""event":"ownership_proposal""#));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.accept_ownership();
+
+        assert_eq!(contract.owner_id(), Some(alice()));
+        assert_eq!(contract.pending_owner_id(), None);
+        assert!(contract.has_role(alice(), Role::Owner));
+        assert!(!contract.has_role(owner(), Role::Owner));
+        assert!(get_logs()[0].contains(r#""event":"ownership_transfer""#));
+
+        // 原所有者的初始角色转给新所有者, 每个角色先撤销再授予
+        for (index, role) in OWNER_ROLES.into_iter().enumerate() {
+            assert!(!contract.has_role(owner(), role));
+            assert!(contract.has_role(alice(), role));
+
+            let revoke = &get_logs()[1 + 2 * index];
+            assert!(revoke.contains(r#""event":"role_revoke""#));
+            assert!(revoke.contains(r#""account_id":"owner.near""#));
+            assert!(revoke.contains(&format!(r#""role":"{}""#, role.as_str())));
+            let grant = &get_logs()[2 + 2 * index];
+            assert!(grant.contains(r#""event":"role_grant""#));
+            assert!(grant.contains(r#""account_id":"alice.near""#));
+            assert!(grant.contains(&format!(r#""role":"{}""#, role.as_str())));
+        }
+    }
+
+    #[test]
+    fn test_accept_ownership_transfers_held_roles_only() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        // 原所有者已放弃 minter 角色, 且 Bob 另外拥有 pauser 角色
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.renounce_role(Role::Minter);
+        contract.grant_role(bob(), Role::Pauser);
+        contract.propose_owner(alice());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.accept_ownership();
+
+        assert!(!contract.has_role(alice(), Role::Minter));
+        assert!(contract.has_role(alice(), Role::Burner));
+        assert!(contract.has_role(bob(), Role::Pauser));
+        assert_eq!(get_logs().len(), 1 + 2 * (OWNER_ROLES.len() - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only pending owner can call this method.")]
+    fn test_accept_ownership_by_other_account() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.propose_owner(alice());
+
+        // Bob 试图抢占所有权
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only pending owner can call this method.")]
+    fn test_accept_ownership_without_proposal() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_accept_ownership_without_deposit() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.propose_owner(alice());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .build());
+
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only contract owner can call this method.")]
+    fn test_propose_owner_by_other_account() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        // Bob 试图把所有权提议给自己
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .build());
+
+        contract.propose_owner(bob());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only pending owner can call this method.")]
+    fn test_accept_cancelled_proposal() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.propose_owner(alice());
+        contract.cancel_ownership_proposal();
+
+        assert_eq!(contract.pending_owner_id(), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only contract owner can call this method.")]
+    fn test_renounce_ownership() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.propose_owner(alice());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.renounce_ownership();
+
+        assert_eq!(contract.owner_id(), None);
+        assert_eq!(contract.pending_owner_id(), None);
+        assert!(contract
+            .accounts_with_role(Role::Owner, None, None)
+            .is_empty());
+
+        // 原所有者的初始角色被撤销
+        for role in OWNER_ROLES {
+            assert!(!contract.has_role(owner(), role));
+            assert!(contract.accounts_with_role(role, None, None).is_empty());
+        }
+        assert_eq!(
+            get_logs()
+                .iter()
+                .filter(|log| log.contains(r#""event":"role_revoke""#))
+                .count(),
+            OWNER_ROLES.len()
+        );
+
+        // 放弃所有权后, 原所有者不能再调用仅限所有者的方法
+        contract.propose_owner(owner());
+    }
+}
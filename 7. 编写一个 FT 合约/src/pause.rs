@@ -0,0 +1,314 @@
+use crate::events::{AccountFreeze, AccountUnfreeze, Pause, Unpause};
+use crate::roles::Role;
+use crate::{Contract, ContractExt};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+// 可以被单独暂停的功能
+#[derive(
+    BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    Transfer,
+    Mint,
+    Burn,
+}
+
+impl Feature {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feature::Transfer => "transfer",
+            Feature::Mint => "mint",
+            Feature::Burn => "burn",
+        }
+    }
+}
+
+// 各功能的暂停状态
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct PausedFeatures {
+    transfer: bool,
+    mint: bool,
+    burn: bool,
+}
+
+impl PausedFeatures {
+    pub fn is_paused(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Transfer => self.transfer,
+            Feature::Mint => self.mint,
+            Feature::Burn => self.burn,
+        }
+    }
+
+    fn set_paused(&mut self, feature: Feature, paused: bool) {
+        match feature {
+            Feature::Transfer => self.transfer = paused,
+            Feature::Mint => self.mint = paused,
+            Feature::Burn => self.burn = paused,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // pauser 能暂停转账, mint 或 burn 中的任意功能
+    pub fn pause(&mut self, feature: Feature) {
+        self.assert_role(Role::Pauser);
+        require!(
+            !self.paused.is_paused(feature),
+            format!("Feature {} is already paused.", feature.as_str())
+        );
+        self.paused.set_paused(feature, true);
+
+        // 打印事件 log
+        Pause {
+            feature,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // pauser 能恢复被暂停的功能
+    pub fn unpause(&mut self, feature: Feature) {
+        self.assert_role(Role::Pauser);
+        require!(
+            self.paused.is_paused(feature),
+            format!("Feature {} is not paused.", feature.as_str())
+        );
+        self.paused.set_paused(feature, false);
+
+        // 打印事件 log
+        Unpause {
+            feature,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // pauser 能冻结单个账户, 被冻结的账户不能发送和接收 FT
+    pub fn freeze_account(&mut self, account_id: AccountId) {
+        self.assert_role(Role::Pauser);
+        require!(
+            self.frozen_accounts.insert(&account_id),
+            "The account is already frozen."
+        );
+
+        // 打印事件 log
+        AccountFreeze {
+            account_id: &account_id,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    // pauser 能解冻单个账户
+    pub fn unfreeze_account(&mut self, account_id: AccountId) {
+        self.assert_role(Role::Pauser);
+        require!(
+            self.frozen_accounts.remove(&account_id),
+            "The account is not frozen."
+        );
+
+        // 打印事件 log
+        AccountUnfreeze {
+            account_id: &account_id,
+            by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn is_paused(&self, feature: Feature) -> bool {
+        self.paused.is_paused(feature)
+    }
+
+    pub fn is_frozen(&self, account_id: AccountId) -> bool {
+        self.frozen_accounts.contains(&account_id)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn assert_not_paused(&self, feature: Feature) {
+        require!(
+            !self.paused.is_paused(feature),
+            format!("Feature {} is paused.", feature.as_str())
+        );
+    }
+
+    pub(crate) fn assert_not_frozen(&self, account_id: &AccountId) {
+        require!(
+            !self.frozen_accounts.contains(account_id),
+            format!("The account {} is frozen.", account_id)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pause::Feature;
+    use crate::roles::Role;
+    use crate::test::{alice, bob, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, ONE_YOCTO};
+
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.storage_deposit(Some(alice()), None);
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Feature transfer is paused.")]
+    fn test_transfer_when_paused() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.pause(Feature::Transfer);
+
+        assert!(contract.is_paused(Feature::Transfer));
+        assert!(!contract.is_paused(Feature::Mint));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_pause_features_independently() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        // 暂停 mint 和 burn 不影响转账
+        contract.pause(Feature::Mint);
+        contract.pause(Feature::Burn);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+
+        // 恢复 mint 后可以继续 mint
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.unpause(Feature::Mint);
+        contract.mint(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(200 * ONE_TOKEN));
+        assert!(contract.is_paused(Feature::Burn));
+    }
+
+    #[test]
+    #[should_panic(expected = "Feature mint is paused.")]
+    fn test_mint_when_paused() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.pause(Feature::Mint);
+        contract.mint(bob(), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Feature burn is paused.")]
+    fn test_burn_when_paused() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.pause(Feature::Burn);
+        contract.burn(bob(), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account alice.near is frozen.")]
+    fn test_transfer_to_frozen_account() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.freeze_account(alice());
+
+        assert!(contract.is_frozen(alice()));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_unfreeze_account() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.freeze_account(bob());
+        contract.unfreeze_account(bob());
+
+        assert!(!contract.is_frozen(bob()));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only pauser can call this method.")]
+    fn test_pause_without_role() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.revoke_role(owner(), Role::Pauser);
+        contract.pause(Feature::Transfer);
+    }
+}
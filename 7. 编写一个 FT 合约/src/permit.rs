@@ -0,0 +1,364 @@
+// `ft_permit` 的参数与 EIP-2612 的 `permit` 保持一致
+#![allow(clippy::too_many_arguments)]
+
+use crate::allowance::settle_storage_deposit;
+use crate::events::PermitKeyUpdate;
+use crate::{Contract, ContractExt};
+use ed25519_dalek::Signature;
+use near_sdk::borsh::{self, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, require, AccountId, Balance, CurveType, PublicKey,
+};
+
+// 签名消息的前缀, 避免与 NEAR 交易或其他合约的签名消息混淆
+pub const PERMIT_PREFIX: &[u8] = b"hello_ft:permit:";
+
+// 离线签名的授权消息, 类似 EIP-2612
+// 用户用注册在合约中的 ed25519 key 对 `to_bytes()` 签名, 任何人都能提交该签名为其设置授权额度
+#[derive(BorshSerialize)]
+pub struct PermitMessage {
+    pub contract_id: AccountId,
+    pub owner_id: AccountId,
+    pub spender_id: AccountId,
+    pub amount: Balance,
+    pub deadline: u64,
+    pub nonce: u64,
+}
+
+impl PermitMessage {
+    // 构建待签名的数据: `PERMIT_PREFIX` + borsh 序列化的消息
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [PERMIT_PREFIX, &self.try_to_vec().unwrap()].concat()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // 把签名当前交易的 key 注册为签名授权用的 key, 会覆盖之前注册的 key
+    // 调用该方法需要附加 NEAR 支付存储费, 因此只能使用 full access key 调用
+    #[payable]
+    pub fn register_permit_key(&mut self) {
+        require!(
+            env::attached_deposit() >= 1,
+            "Requires attached deposit of at least 1 yoctoNEAR"
+        );
+        let account_id = env::predecessor_account_id();
+        require!(
+            account_id == env::signer_account_id(),
+            "Permit key can only be registered by the signer."
+        );
+        let public_key = env::signer_account_pk();
+        require!(
+            public_key.curve_type() == CurveType::ED25519,
+            "Only ed25519 key is supported."
+        );
+
+        // 注销 key 后保留 nonce, 防止重新注册同一个 key 后旧签名被重放
+        let initial_storage_usage = env::storage_usage();
+        self.permit_keys.insert(&account_id, &public_key);
+        if !self.permit_nonces.contains_key(&account_id) {
+            self.permit_nonces.insert(&account_id, &0);
+        }
+        settle_storage_deposit(initial_storage_usage, &account_id);
+
+        // 打印事件 log
+        PermitKeyUpdate {
+            account_id: &account_id,
+            public_key: Some(&public_key),
+        }
+        .emit();
+    }
+
+    // 注销签名授权用的 key 并退还存储费. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn remove_permit_key(&mut self) {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+        require!(
+            self.permit_keys.remove(&account_id).is_some(),
+            "The account does not have a permit key."
+        );
+        settle_storage_deposit(initial_storage_usage, &account_id);
+
+        // 打印事件 log
+        PermitKeyUpdate {
+            account_id: &account_id,
+            public_key: None,
+        }
+        .emit();
+    }
+
+    pub fn ft_permit_key(&self, account_id: AccountId) -> Option<PublicKey> {
+        self.permit_keys.get(&account_id)
+    }
+
+    // 查询 `owner_id` 下一个签名授权应使用的 nonce
+    pub fn ft_permit_nonce(&self, owner_id: AccountId) -> U64 {
+        U64(self.permit_nonces.get(&owner_id).unwrap_or(0))
+    }
+
+    // 提交 `owner_id` 离线签名的授权, 设置 `spender_id` 的授权额度
+    // `deadline` 为区块时间戳 (纳秒). 新建授权记录的存储费由调用者附加的 NEAR 支付
+    #[payable]
+    pub fn ft_permit(
+        &mut self,
+        owner_id: AccountId,
+        spender_id: AccountId,
+        amount: U128,
+        deadline: U64,
+        nonce: U64,
+        public_key: PublicKey,
+        signature: Base64VecU8,
+    ) {
+        require!(env::block_timestamp() <= deadline.0, "Permit expired.");
+        require!(
+            self.permit_keys.get(&owner_id).as_ref() == Some(&public_key),
+            "Public key is not registered for the owner."
+        );
+        let expected_nonce = self.permit_nonces.get(&owner_id).unwrap_or(0);
+        require!(nonce.0 == expected_nonce, "Invalid permit nonce.");
+
+        let message = PermitMessage {
+            contract_id: env::current_account_id(),
+            owner_id: owner_id.clone(),
+            spender_id: spender_id.clone(),
+            amount: amount.0,
+            deadline: deadline.0,
+            nonce: nonce.0,
+        };
+        require!(
+            verify_ed25519(&public_key, &message.to_bytes(), &signature.0),
+            "Invalid permit signature."
+        );
+
+        self.permit_nonces.insert(&owner_id, &(expected_nonce + 1));
+        self.internal_approve(&owner_id, &spender_id, amount.0);
+    }
+}
+
+// 使用 ed25519 公钥验证签名
+fn verify_ed25519(public_key: &PublicKey, message: &[u8], signature: &[u8]) -> bool {
+    // `PublicKey` 的第一个字节为曲线类型
+    let public_key = match ed25519_dalek::PublicKey::from_bytes(&public_key.as_bytes()[1..]) {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    let signature = match Signature::try_from(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    public_key.verify_strict(message, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::permit::PermitMessage;
+    use crate::test::{alice, bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use ed25519_dalek::{ExpandedSecretKey, SecretKey};
+    use near_sdk::json_types::{Base64VecU8, U128, U64};
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, PublicKey, ONE_NEAR, ONE_YOCTO};
+
+    const DEADLINE: u64 = 1_000_000_000;
+
+    fn relayer() -> AccountId {
+        "relayer.near".parse().unwrap()
+    }
+
+    fn contract_id() -> AccountId {
+        "hello_ft.near".parse().unwrap()
+    }
+
+    fn secret_key() -> SecretKey {
+        SecretKey::from_bytes(&[7; 32]).unwrap()
+    }
+
+    fn public_key() -> PublicKey {
+        let public_key = ed25519_dalek::PublicKey::from(&secret_key());
+        PublicKey::try_from([&[0], public_key.as_bytes().as_slice()].concat()).unwrap()
+    }
+
+    fn sign(message: &PermitMessage) -> Base64VecU8 {
+        let secret_key = secret_key();
+        let public_key = ed25519_dalek::PublicKey::from(&secret_key);
+        let signature = ExpandedSecretKey::from(&secret_key).sign(&message.to_bytes(), &public_key);
+        Base64VecU8(signature.to_bytes().to_vec())
+    }
+
+    fn permit_message(amount: u128, nonce: u64) -> PermitMessage {
+        PermitMessage {
+            contract_id: contract_id(),
+            owner_id: bob(),
+            spender_id: alice(),
+            amount,
+            deadline: DEADLINE,
+            nonce,
+        }
+    }
+
+    // Bob 注册签名授权用的 key, 之后的调用均由 relayer 发起
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .signer_account_id(bob())
+            .signer_account_pk(public_key())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+
+        contract.register_permit_key();
+
+        assert_eq!(contract.ft_permit_key(bob()), Some(public_key()));
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(relayer())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+
+        contract
+    }
+
+    #[test]
+    fn test_permit() {
+        let mut contract = setup();
+        let message = permit_message(100 * ONE_TOKEN, 0);
+
+        contract.ft_permit(
+            bob(),
+            alice(),
+            U128(100 * ONE_TOKEN),
+            U64(DEADLINE),
+            U64(0),
+            public_key(),
+            sign(&message),
+        );
+
+        assert_eq!(contract.ft_allowance(bob(), alice()), U128(100 * ONE_TOKEN));
+        assert_eq!(contract.ft_permit_nonce(bob()), U64(1));
+    }
+
+    #[test]
+    fn test_permit_storage_refunded_to_relayer() {
+        let mut contract = setup();
+        let message = permit_message(100 * ONE_TOKEN, 0);
+
+        contract.ft_permit(
+            bob(),
+            alice(),
+            U128(100 * ONE_TOKEN),
+            U64(DEADLINE),
+            U64(0),
+            public_key(),
+            sign(&message),
+        );
+
+        // relayer 附加的 NEAR 扣除存储费后退还
+        let storage_cost = match &get_created_receipts()[0].actions[..] {
+            [VmAction::Transfer { deposit }] => ONE_NEAR / 100 - deposit,
+            _ => unreachable!(),
+        };
+
+        // Bob 取消授权, 释放的存储费退还给 relayer, Bob 附加的 1 yocto NEAR 退还给 Bob
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_decrease_allowance(alice(), U128(100 * ONE_TOKEN));
+
+        let refunds: Vec<_> = get_created_receipts()
+            .into_iter()
+            .map(|receipt| match &receipt.actions[..] {
+                [VmAction::Transfer { deposit }] => (receipt.receiver_id.clone(), *deposit),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(refunds, vec![(bob(), ONE_YOCTO), (relayer(), storage_cost)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid permit nonce.")]
+    fn test_permit_replay() {
+        let mut contract = setup();
+        let signature = sign(&permit_message(100 * ONE_TOKEN, 0));
+
+        for _ in 0..2 {
+            contract.ft_permit(
+                bob(),
+                alice(),
+                U128(100 * ONE_TOKEN),
+                U64(DEADLINE),
+                U64(0),
+                public_key(),
+                signature.clone(),
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid permit signature.")]
+    fn test_permit_with_tampered_amount() {
+        let mut contract = setup();
+        let signature = sign(&permit_message(100 * ONE_TOKEN, 0));
+
+        contract.ft_permit(
+            bob(),
+            alice(),
+            U128(1000 * ONE_TOKEN),
+            U64(DEADLINE),
+            U64(0),
+            public_key(),
+            signature,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Permit expired.")]
+    fn test_permit_expired() {
+        let mut contract = setup();
+        let signature = sign(&permit_message(100 * ONE_TOKEN, 0));
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(relayer())
+            .attached_deposit(ONE_NEAR / 100)
+            .block_timestamp(DEADLINE + 1)
+            .build());
+
+        contract.ft_permit(
+            bob(),
+            alice(),
+            U128(100 * ONE_TOKEN),
+            U64(DEADLINE),
+            U64(0),
+            public_key(),
+            signature,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Public key is not registered for the owner.")]
+    fn test_permit_with_unregistered_key() {
+        let mut contract = setup();
+        let mut message = permit_message(100 * ONE_TOKEN, 0);
+        message.owner_id = owner();
+
+        contract.ft_permit(
+            owner(),
+            alice(),
+            U128(100 * ONE_TOKEN),
+            U64(DEADLINE),
+            U64(0),
+            public_key(),
+            sign(&message),
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use crate::events::{EpochMintCapUpdate, MintQuotaUpdate};
+use crate::{Contract, ContractExt};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::{env, near_bindgen, require, AccountId, Balance, EpochHeight};
+
+// 滚动窗口的窗口期被均分为多少个时间段
+const MINT_QUOTA_BUCKETS: u64 = 10;
+
+// minter 的滚动窗口 mint 额度: 任意长度为 `window` 的时间段内最多 mint `limit`
+// 按时间段记录已 mint 的数量, 每个时间段的额度在该时间段结束一个窗口期之后释放, 额度随时间逐段恢复
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RollingMintQuota {
+    limit: Balance,
+    // 窗口期长度 (纳秒)
+    window: u64,
+    // (时间段序号, 该时间段内 mint 的数量), 按时间顺序排列
+    buckets: Vec<(u64, Balance)>,
+}
+
+impl RollingMintQuota {
+    fn new(limit: Balance, window: u64) -> Self {
+        Self {
+            limit,
+            window,
+            buckets: vec![],
+        }
+    }
+
+    // 时间段长度向上取整, 保证未释放的时间段覆盖完整的窗口期
+    fn bucket_len(&self) -> u64 {
+        self.window.div_ceil(MINT_QUOTA_BUCKETS)
+    }
+
+    // 时间段的额度释放的时间戳
+    fn released_at(&self, bucket: u64) -> u64 {
+        (bucket + MINT_QUOTA_BUCKETS + 1).saturating_mul(self.bucket_len())
+    }
+
+    // 尚未释放额度的时间段
+    fn live_buckets(&self, timestamp: u64) -> impl Iterator<Item = &(u64, Balance)> {
+        self.buckets
+            .iter()
+            .filter(move |(bucket, _)| self.released_at(*bucket) > timestamp)
+    }
+
+    fn used(&self, timestamp: u64) -> Balance {
+        self.live_buckets(timestamp).map(|(_, minted)| minted).sum()
+    }
+
+    fn remaining(&self, timestamp: u64) -> Balance {
+        self.limit.saturating_sub(self.used(timestamp))
+    }
+
+    // 下一部分额度释放的时间戳, 没有未释放的额度时为当前时间
+    fn refreshes_at(&self, timestamp: u64) -> u64 {
+        self.live_buckets(timestamp)
+            .next()
+            .map_or(timestamp, |(bucket, _)| self.released_at(*bucket))
+    }
+
+    // 剩余额度足够 mint `amount` 的最早时间戳, `amount` 不能超过 `limit`
+    fn available_at(&self, timestamp: u64, amount: Balance) -> u64 {
+        let mut used = self.used(timestamp);
+        if self.limit.saturating_sub(used) >= amount {
+            return timestamp;
+        }
+        self.live_buckets(timestamp)
+            .find_map(|(bucket, minted)| {
+                used -= minted;
+                (self.limit.saturating_sub(used) >= amount).then(|| self.released_at(*bucket))
+            })
+            .unwrap_or(timestamp)
+    }
+
+    // 记录 mint 的数量, 同时删除已释放的时间段
+    fn record(&mut self, timestamp: u64, amount: Balance) {
+        let bucket_len = self.bucket_len();
+        let bucket = timestamp / bucket_len;
+        self.buckets.retain(|(bucket, _)| {
+            (bucket + MINT_QUOTA_BUCKETS + 1).saturating_mul(bucket_len) > timestamp
+        });
+        match self.buckets.last_mut() {
+            Some((last_bucket, minted)) if *last_bucket == bucket => *minted += amount,
+            _ => self.buckets.push((bucket, amount)),
+        }
+    }
+
+    // 修改窗口期会改变时间段的划分, 未释放的额度合并到当前时间段, 从现在开始重新计算一个窗口期
+    fn set_window(&mut self, timestamp: u64, window: u64) {
+        if window == self.window {
+            return;
+        }
+        let used = self.used(timestamp);
+        self.window = window;
+        self.buckets.clear();
+        if used > 0 {
+            self.buckets.push((timestamp / self.bucket_len(), used));
+        }
+    }
+}
+
+// 当前 epoch 内 minter 已 mint 的总量
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct EpochMinted {
+    epoch_height: EpochHeight,
+    minted: Balance,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintQuotaView {
+    pub limit: U128,
+    pub window: U64,
+    pub remaining: U128,
+    // 下一部分额度释放的时间戳 (纳秒)
+    pub refreshes_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    // 合约所有者能为 minter 设置滚动窗口期内的 mint 额度, 窗口期单位为纳秒
+    // 修改已有额度时保留未释放的已 mint 数量, 避免通过修改额度重置窗口
+    pub fn set_mint_quota(&mut self, minter_id: AccountId, limit: U128, window: U64) {
+        self.assert_owner();
+        require!(window.0 > 0, "Quota window must be positive.");
+        let quota = match self.mint_quotas.get(&minter_id) {
+            Some(mut quota) => {
+                quota.limit = limit.0;
+                quota.set_window(env::block_timestamp(), window.0);
+                quota
+            }
+            None => RollingMintQuota::new(limit.0, window.0),
+        };
+        self.mint_quotas.insert(&minter_id, &quota);
+
+        // 打印事件 log
+        MintQuotaUpdate {
+            minter_id: &minter_id,
+            limit: Some(&limit),
+            window: Some(&window),
+        }
+        .emit();
+    }
+
+    // 合约所有者能移除 minter 的 mint 额度限制
+    pub fn remove_mint_quota(&mut self, minter_id: AccountId) {
+        self.assert_owner();
+        require!(
+            self.mint_quotas.remove(&minter_id).is_some(),
+            "The minter does not have a mint quota."
+        );
+
+        // 打印事件 log
+        MintQuotaUpdate {
+            minter_id: &minter_id,
+            limit: None,
+            window: None,
+        }
+        .emit();
+    }
+
+    // 合约所有者能设置每个 epoch 内所有 minter 合计能 mint 的上限, `None` 表示没有上限
+    pub fn set_epoch_mint_cap(&mut self, epoch_mint_cap: Option<U128>) {
+        self.assert_owner();
+        let old_epoch_mint_cap = self.epoch_mint_cap.map(U128);
+        self.epoch_mint_cap = epoch_mint_cap.map(|cap| cap.0);
+
+        // 打印事件 log
+        EpochMintCapUpdate {
+            old_epoch_mint_cap: old_epoch_mint_cap.as_ref(),
+            new_epoch_mint_cap: epoch_mint_cap.as_ref(),
+        }
+        .emit();
+    }
+
+    pub fn mint_quota_of(&self, minter_id: AccountId) -> Option<MintQuotaView> {
+        let timestamp = env::block_timestamp();
+        self.mint_quotas.get(&minter_id).map(|quota| MintQuotaView {
+            limit: U128(quota.limit),
+            window: U64(quota.window),
+            remaining: U128(quota.remaining(timestamp)),
+            refreshes_at: U64(quota.refreshes_at(timestamp)),
+        })
+    }
+
+    // 查询 minter 当前还能 mint 的数量, 同时受 minter 额度和 epoch 上限限制, `None` 表示没有限制
+    pub fn remaining_mint_quota(&self, minter_id: AccountId) -> Option<U128> {
+        let quota_remaining = self
+            .mint_quotas
+            .get(&minter_id)
+            .map(|quota| quota.remaining(env::block_timestamp()));
+        let epoch_remaining = self.internal_remaining_epoch_mint();
+        match (quota_remaining, epoch_remaining) {
+            (Some(a), Some(b)) => Some(U128(std::cmp::min(a, b))),
+            (a, b) => a.or(b).map(U128),
+        }
+    }
+
+    pub fn epoch_mint_cap(&self) -> Option<U128> {
+        self.epoch_mint_cap.map(U128)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    fn internal_remaining_epoch_mint(&self) -> Option<Balance> {
+        self.epoch_mint_cap.map(|cap| {
+            if self.epoch_minted.epoch_height == env::epoch_height() {
+                cap.saturating_sub(self.epoch_minted.minted)
+            } else {
+                cap
+            }
+        })
+    }
+
+    // 消耗 minter 的 mint 额度和当前 epoch 的 mint 上限
+    pub(crate) fn internal_use_mint_quota(&mut self, minter_id: &AccountId, amount: Balance) {
+        if let Some(mut quota) = self.mint_quotas.get(minter_id) {
+            let timestamp = env::block_timestamp();
+            require!(
+                amount <= quota.limit,
+                format!(
+                    "Mint quota exceeded, the amount is greater than the quota limit {}.",
+                    quota.limit
+                )
+            );
+            let remaining = quota.remaining(timestamp);
+            require!(
+                remaining >= amount,
+                format!(
+                    "Mint quota exceeded, {} remaining, enough quota is available at timestamp {}.",
+                    remaining,
+                    quota.available_at(timestamp, amount)
+                )
+            );
+            quota.record(timestamp, amount);
+            self.mint_quotas.insert(minter_id, &quota);
+        }
+
+        if let Some(remaining) = self.internal_remaining_epoch_mint() {
+            let epoch_height = env::epoch_height();
+            require!(
+                remaining >= amount,
+                format!(
+                    "Epoch mint cap exceeded, {} remaining, refreshes at epoch {}.",
+                    remaining,
+                    epoch_height + 1
+                )
+            );
+            if self.epoch_minted.epoch_height != epoch_height {
+                self.epoch_minted = EpochMinted {
+                    epoch_height,
+                    minted: 0,
+                };
+            }
+            self.epoch_minted.minted += amount;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const HOUR: u64 = 60 * 60 * 1_000_000_000;
+    // 一小时的窗口期中每个时间段的长度
+    const SLOT: u64 = HOUR / 10;
+
+    // owner 每小时最多 mint 100 FT
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.set_mint_quota(owner(), U128(100 * ONE_TOKEN), U64(HOUR));
+        contract
+    }
+
+    #[test]
+    fn test_mint_quota_refresh() {
+        let mut contract = setup();
+
+        contract.mint(alice(), U128(60 * ONE_TOKEN), None);
+
+        assert_eq!(
+            contract.remaining_mint_quota(owner()),
+            Some(U128(40 * ONE_TOKEN))
+        );
+        assert_eq!(
+            contract.mint_quota_of(owner()).unwrap().refreshes_at,
+            U64(11 * SLOT)
+        );
+
+        // 第一个时间段结束一个窗口期之后额度才释放
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(11 * SLOT - 1)
+            .build());
+
+        assert_eq!(
+            contract.remaining_mint_quota(owner()),
+            Some(U128(40 * ONE_TOKEN))
+        );
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(11 * SLOT)
+            .build());
+
+        assert_eq!(
+            contract.remaining_mint_quota(owner()),
+            Some(U128(100 * ONE_TOKEN))
+        );
+
+        contract.mint(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(160 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut contract = setup();
+
+        // 窗口期内分两次 mint, 额度按时间段分别释放
+        contract.mint(alice(), U128(60 * ONE_TOKEN), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(5 * SLOT)
+            .build());
+
+        contract.mint(alice(), U128(40 * ONE_TOKEN), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(11 * SLOT)
+            .build());
+
+        assert_eq!(
+            contract.remaining_mint_quota(owner()),
+            Some(U128(60 * ONE_TOKEN))
+        );
+        assert_eq!(
+            contract.mint_quota_of(owner()).unwrap().refreshes_at,
+            U64(16 * SLOT)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint quota exceeded, 0 remaining")]
+    fn test_mint_across_window_boundary() {
+        let mut contract = setup();
+
+        // 在一个小时结束前用完额度, 下一个小时开始时仍不能 mint
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(HOUR - 1)
+            .build());
+
+        contract.mint(alice(), U128(100 * ONE_TOKEN), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(HOUR)
+            .build());
+
+        contract.mint(alice(), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Mint quota exceeded, 40000000000000000000 remaining, enough quota is available at timestamp 3960000000000."
+    )]
+    fn test_mint_over_quota() {
+        let mut contract = setup();
+
+        contract.mint(alice(), U128(60 * ONE_TOKEN), None);
+        contract.mint_batch(
+            vec![
+                (alice(), U128(20 * ONE_TOKEN)),
+                (bob(), U128(21 * ONE_TOKEN)),
+            ],
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Epoch mint cap exceeded, 0 remaining, refreshes at epoch 1.")]
+    fn test_mint_over_epoch_cap() {
+        let mut contract = setup();
+
+        contract.remove_mint_quota(owner());
+        contract.set_epoch_mint_cap(Some(U128(100 * ONE_TOKEN)));
+        contract.mint(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.remaining_mint_quota(owner()), Some(U128(0)));
+
+        contract.mint(alice(), U128(1), None);
+    }
+
+    #[test]
+    fn test_epoch_cap_refresh() {
+        let mut contract = setup();
+
+        contract.set_epoch_mint_cap(Some(U128(50 * ONE_TOKEN)));
+        contract.mint(alice(), U128(50 * ONE_TOKEN), None);
+
+        // 下一个 epoch 上限刷新, 但 minter 额度仍在同一个窗口期内
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .epoch_height(1)
+            .build());
+
+        assert_eq!(
+            contract.remaining_mint_quota(owner()),
+            Some(U128(50 * ONE_TOKEN))
+        );
+
+        contract.mint(alice(), U128(50 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_total_supply(), U128(100 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_update_quota_keeps_usage() {
+        let mut contract = setup();
+
+        contract.mint(alice(), U128(60 * ONE_TOKEN), None);
+
+        // 修改额度不会重置当前窗口已 mint 的数量
+        contract.set_mint_quota(owner(), U128(80 * ONE_TOKEN), U64(HOUR));
+
+        assert_eq!(
+            contract.remaining_mint_quota(owner()),
+            Some(U128(20 * ONE_TOKEN))
+        );
+
+        // 额度降到已 mint 的数量以下时, 当前窗口内不能再 mint
+        contract.set_mint_quota(owner(), U128(50 * ONE_TOKEN), U64(2 * HOUR));
+
+        assert_eq!(contract.remaining_mint_quota(owner()), Some(U128(0)));
+        assert_eq!(
+            contract.mint_quota_of(owner()).unwrap().refreshes_at,
+            U64(11 * 2 * SLOT)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint quota exceeded, the amount is greater than the quota limit")]
+    fn test_mint_over_quota_limit() {
+        let mut contract = setup();
+
+        contract.mint(alice(), U128(101 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint quota exceeded, 20000000000000000000 remaining")]
+    fn test_mint_over_updated_quota() {
+        let mut contract = setup();
+
+        contract.mint(alice(), U128(60 * ONE_TOKEN), None);
+        contract.set_mint_quota(owner(), U128(80 * ONE_TOKEN), U64(HOUR));
+
+        contract.mint(alice(), U128(21 * ONE_TOKEN), None);
+    }
+}
@@ -0,0 +1,383 @@
+use crate::events::{RoleGrant, RoleRevoke};
+use crate::{Contract, ContractExt, StorageKey};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::json_types::U64;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+// 每次最多查询的角色账户数, 也是不指定 `limit` 时的默认值
+pub const MAX_ROLE_ACCOUNTS_LIMIT: u64 = 100;
+
+// 合约所有者初始拥有的角色, 转移所有权时随之转移
+pub const OWNER_ROLES: [Role; 4] = [
+    Role::Minter,
+    Role::Burner,
+    Role::Pauser,
+    Role::MetadataAdmin,
+];
+
+// 合约中的角色
+// `Owner` 即 `owner_id`, 只能通过转移所有权变更, 负责授予和撤销其他角色
+#[derive(
+    BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Owner,
+    Minter,
+    Burner,
+    Pauser,
+    MetadataAdmin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Minter => "minter",
+            Role::Burner => "burner",
+            Role::Pauser => "pauser",
+            Role::MetadataAdmin => "metadata_admin",
+        }
+    }
+
+    fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+// 使用位图存储一个账户拥有的所有角色
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Default)]
+pub struct RoleSet(u8);
+
+impl RoleSet {
+    pub fn contains(&self, role: Role) -> bool {
+        self.0 & role.bit() != 0
+    }
+
+    pub fn insert(&mut self, role: Role) -> bool {
+        let inserted = !self.contains(role);
+        self.0 |= role.bit();
+        inserted
+    }
+
+    pub fn remove(&mut self, role: Role) -> bool {
+        let removed = self.contains(role);
+        self.0 &= !role.bit();
+        removed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // 合约所有者能给任意账户授予角色
+    pub fn grant_role(&mut self, account_id: AccountId, role: Role) {
+        self.assert_owner();
+        require!(
+            role != Role::Owner,
+            "Owner role can only be changed by transferring ownership."
+        );
+        if self.internal_grant_role(&account_id, role) {
+            // 打印事件 log
+            RoleGrant {
+                account_id: &account_id,
+                role,
+                by: &env::predecessor_account_id(),
+            }
+            .emit();
+        }
+    }
+
+    // 合约所有者能撤销任意账户的角色
+    pub fn revoke_role(&mut self, account_id: AccountId, role: Role) {
+        self.assert_owner();
+        require!(
+            role != Role::Owner,
+            "Owner role can only be changed by transferring ownership."
+        );
+        if self.internal_revoke_role(&account_id, role) {
+            // 打印事件 log
+            RoleRevoke {
+                account_id: &account_id,
+                role,
+                by: &env::predecessor_account_id(),
+            }
+            .emit();
+        }
+    }
+
+    // 账户主动放弃自己的角色
+    pub fn renounce_role(&mut self, role: Role) {
+        require!(
+            role != Role::Owner,
+            "Owner role can only be changed by transferring ownership."
+        );
+        let account_id = env::predecessor_account_id();
+        require!(
+            self.internal_revoke_role(&account_id, role),
+            format!("Account does not have {} role.", role.as_str())
+        );
+
+        // 打印事件 log
+        RoleRevoke {
+            account_id: &account_id,
+            role,
+            by: &account_id,
+        }
+        .emit();
+    }
+
+    pub fn has_role(&self, account_id: AccountId, role: Role) -> bool {
+        self.internal_has_role(&account_id, role)
+    }
+
+    // 分页查询拥有角色的账户, 顺序与授予顺序无关, 撤销角色后顺序会改变
+    pub fn accounts_with_role(
+        &self,
+        role: Role,
+        from_index: Option<U64>,
+        limit: Option<U64>,
+    ) -> Vec<AccountId> {
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(MAX_ROLE_ACCOUNTS_LIMIT, |limit| limit.0);
+        if role == Role::Owner {
+            return self
+                .owner_id
+                .iter()
+                .skip(from_index as usize)
+                .take(limit.min(MAX_ROLE_ACCOUNTS_LIMIT) as usize)
+                .cloned()
+                .collect();
+        }
+        let accounts = match self.role_accounts.get(&role) {
+            Some(accounts) => accounts,
+            None => return vec![],
+        };
+        let accounts = accounts.as_vector();
+        let end = from_index
+            .saturating_add(limit.min(MAX_ROLE_ACCOUNTS_LIMIT))
+            .min(accounts.len());
+        (from_index..end)
+            .map(|index| accounts.get(index).unwrap())
+            .collect()
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn assert_role(&self, role: Role) {
+        require!(
+            self.internal_has_role(&env::predecessor_account_id(), role),
+            format!("Only {} can call this method.", role.as_str())
+        );
+    }
+
+    pub(crate) fn internal_has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        if role == Role::Owner {
+            return self.owner_id.as_ref() == Some(account_id);
+        }
+        self.roles
+            .get(account_id)
+            .map(|roles| roles.contains(role))
+            .unwrap_or(false)
+    }
+
+    // 原所有者拥有的 `OWNER_ROLES` 转给新所有者, `new_owner_id` 为 `None` 表示所有权被放弃, 只撤销角色
+    pub(crate) fn internal_transfer_owner_roles(
+        &mut self,
+        old_owner_id: &AccountId,
+        new_owner_id: Option<&AccountId>,
+    ) {
+        let by = env::predecessor_account_id();
+        for role in OWNER_ROLES {
+            if !self.internal_revoke_role(old_owner_id, role) {
+                continue;
+            }
+
+            // 打印事件 log
+            RoleRevoke {
+                account_id: old_owner_id,
+                role,
+                by: &by,
+            }
+            .emit();
+
+            if let Some(new_owner_id) = new_owner_id {
+                if self.internal_grant_role(new_owner_id, role) {
+                    // 打印事件 log
+                    RoleGrant {
+                        account_id: new_owner_id,
+                        role,
+                        by: &by,
+                    }
+                    .emit();
+                }
+            }
+        }
+    }
+
+    // 返回值表示账户是否新获得了该角色
+    pub(crate) fn internal_grant_role(&mut self, account_id: &AccountId, role: Role) -> bool {
+        let mut roles = self.roles.get(account_id).unwrap_or_default();
+        if !roles.insert(role) {
+            return false;
+        }
+        self.roles.insert(account_id, &roles);
+
+        let mut accounts = self
+            .role_accounts
+            .get(&role)
+            .unwrap_or_else(|| UnorderedSet::new(StorageKey::RoleMembers { role }));
+        accounts.insert(account_id);
+        self.role_accounts.insert(&role, &accounts);
+        true
+    }
+
+    // 返回值表示账户是否确实失去了该角色
+    pub(crate) fn internal_revoke_role(&mut self, account_id: &AccountId, role: Role) -> bool {
+        let mut roles = match self.roles.get(account_id) {
+            Some(roles) => roles,
+            None => return false,
+        };
+        if !roles.remove(role) {
+            return false;
+        }
+        if roles.is_empty() {
+            self.roles.remove(account_id);
+        } else {
+            self.roles.insert(account_id, &roles);
+        }
+
+        if let Some(mut accounts) = self.role_accounts.get(&role) {
+            accounts.remove(account_id);
+            self.role_accounts.insert(&role, &accounts);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::roles::Role;
+    use crate::test::{alice, bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_owner_has_all_roles_after_init() {
+        let contract = Contract::init(owner(), metadata(), None, None);
+
+        for role in [
+            Role::Owner,
+            Role::Minter,
+            Role::Burner,
+            Role::Pauser,
+            Role::MetadataAdmin,
+        ] {
+            assert!(contract.has_role(owner(), role));
+            assert_eq!(contract.accounts_with_role(role, None, None), vec![owner()]);
+        }
+    }
+
+    #[test]
+    fn test_grant_revoke_renounce() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        // ------------------------------ 授予 Alice minter 角色 ------------------------------------
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.grant_role(alice(), Role::Minter);
+        contract.grant_role(bob(), Role::Minter);
+
+        assert!(contract.has_role(alice(), Role::Minter));
+        assert!(!contract.has_role(alice(), Role::Burner));
+        assert_eq!(
+            contract.accounts_with_role(Role::Minter, None, None),
+            vec![owner(), alice(), bob()]
+        );
+        assert_eq!(
+            contract.accounts_with_role(Role::Minter, Some(U64(1)), Some(U64(1))),
+            vec![alice()]
+        );
+        assert!(get_logs()[0].contains(r#""event":"role_grant""#));
+
+        // --------------------------------- Alice mint FT -----------------------------------------
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .build());
+
+        contract.mint(bob(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(100 * ONE_TOKEN));
+
+        // ------------------------------ Alice 放弃 minter 角色 ------------------------------------
+
+        contract.renounce_role(Role::Minter);
+
+        assert!(!contract.has_role(alice(), Role::Minter));
+        assert!(get_logs()[1].contains(r#""event":"role_revoke""#));
+
+        // ------------------------------- 撤销 Bob 的 minter 角色 -----------------------------------
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.revoke_role(bob(), Role::Minter);
+
+        assert_eq!(
+            contract.accounts_with_role(Role::Minter, None, None),
+            vec![owner()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only minter can call this method.")]
+    fn test_mint_without_role() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .build());
+
+        contract.mint(bob(), U128(100 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only contract owner can call this method.")]
+    fn test_grant_role_without_permission() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .build());
+
+        contract.grant_role(alice(), Role::Minter);
+    }
+
+    #[test]
+    #[should_panic(expected = "Owner role can only be changed by transferring ownership.")]
+    fn test_grant_owner_role() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.grant_role(alice(), Role::Owner);
+    }
+}
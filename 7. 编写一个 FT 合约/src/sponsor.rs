@@ -0,0 +1,379 @@
+use crate::events::{RegistrationSponsorUpdate, SponsoredRegistration};
+use crate::{Contract, ContractExt, StorageKey};
+use near_contract_standards::fungible_token::events::FtBurn;
+use near_contract_standards::storage_management::StorageManagement;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, require, AccountId, Balance, Promise, StorageUsage,
+};
+
+// 由合约代付存储费的注册, 转账给未注册的账户时自动为其注册
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RegistrationSponsor {
+    // 剩余可用于代付存储费的 NEAR, 为 0 表示关闭代付
+    budget: Balance,
+    // 每个发送者在一个周期内最多能触发的代付次数
+    max_per_sender: u32,
+    // 限流周期 (纳秒)
+    period: u64,
+    // 累计代付注册的账户数
+    total: u64,
+    // 发送者在当前周期内的代付次数
+    usages: LookupMap<AccountId, SponsorUsage>,
+    // 由合约代付存储费的账户, 注销时不退还存储费
+    accounts: LookupSet<AccountId>,
+    // 一个发送者的限流记录和一个代付账户的记录最多占用的存储
+    storage_usage: StorageUsage,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SponsorUsage {
+    period_start: u64,
+    count: u32,
+}
+
+impl RegistrationSponsor {
+    pub fn new() -> Self {
+        Self {
+            budget: 0,
+            max_per_sender: 0,
+            period: 0,
+            total: 0,
+            usages: LookupMap::new(StorageKey::SponsorUsages),
+            accounts: LookupSet::new(StorageKey::SponsoredAccounts),
+            storage_usage: 0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RegistrationSponsorView {
+    pub budget: U128,
+    pub max_per_sender: u32,
+    pub period: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    // 合约所有者能设置代付预算和每个发送者在 `period` 纳秒内最多触发的代付次数, 预算为 0 时关闭代付
+    pub fn set_registration_sponsor(&mut self, budget: U128, max_per_sender: u32, period: U64) {
+        self.assert_owner();
+        require!(
+            budget.0 == 0 || (max_per_sender > 0 && period.0 > 0),
+            "Rate limit must be positive when sponsoring is enabled."
+        );
+        self.sponsor.budget = budget.0;
+        self.sponsor.max_per_sender = max_per_sender;
+        self.sponsor.period = period.0;
+
+        // 打印事件 log
+        RegistrationSponsorUpdate {
+            budget: &budget,
+            max_per_sender,
+            period: &period,
+        }
+        .emit();
+    }
+
+    pub fn registration_sponsor(&self) -> RegistrationSponsorView {
+        RegistrationSponsorView {
+            budget: U128(self.sponsor.budget),
+            max_per_sender: self.sponsor.max_per_sender,
+            period: U64(self.sponsor.period),
+        }
+    }
+
+    // 查询累计由合约代付存储费注册的账户数
+    pub fn sponsored_registrations(&self) -> U64 {
+        U64(self.sponsor.total)
+    }
+
+    pub fn is_sponsored(&self, account_id: AccountId) -> bool {
+        self.sponsor.accounts.contains(&account_id)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 测量代付注册时除注册本身之外写入的存储
+    pub(crate) fn internal_measure_sponsor_storage_usage(&mut self) {
+        let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
+        let initial_storage_usage = env::storage_usage();
+        self.sponsor.usages.insert(
+            &tmp_account_id,
+            &SponsorUsage {
+                period_start: 0,
+                count: 0,
+            },
+        );
+        self.sponsor.accounts.insert(&tmp_account_id);
+        self.sponsor.storage_usage = env::storage_usage() - initial_storage_usage;
+        self.sponsor.usages.remove(&tmp_account_id);
+        self.sponsor.accounts.remove(&tmp_account_id);
+    }
+
+    // 接收者未注册时尝试由合约代付存储费为其注册, 预算不足时不注册
+    // 注册和发送者的限流记录占用的存储都从预算中扣除, 限流记录在发送者之后触发代付时复用
+    pub(crate) fn internal_sponsor_registration(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+    ) {
+        if self.tokens.accounts.contains_key(receiver_id) {
+            return;
+        }
+        let max_cost = self.storage_balance_bounds().min.0
+            + Balance::from(self.sponsor.storage_usage) * env::storage_byte_cost();
+        if self.sponsor.budget < max_cost {
+            return;
+        }
+
+        // 按发送者限流, 新周期开始时重新计数
+        let now = env::block_timestamp();
+        let mut usage = self
+            .sponsor
+            .usages
+            .get(sender_id)
+            .filter(|usage| now - usage.period_start < self.sponsor.period)
+            .unwrap_or(SponsorUsage {
+                period_start: now,
+                count: 0,
+            });
+        require!(
+            usage.count < self.sponsor.max_per_sender,
+            "Sponsored registration rate limit exceeded."
+        );
+        usage.count += 1;
+
+        let initial_storage_usage = env::storage_usage();
+        self.sponsor.usages.insert(sender_id, &usage);
+        self.sponsor.accounts.insert(receiver_id);
+        self.tokens.internal_register_account(receiver_id);
+        self.internal_index_holder(receiver_id);
+        let cost =
+            Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+        self.sponsor.budget -= cost;
+        self.sponsor.total += 1;
+
+        // 打印事件 log
+        SponsoredRegistration {
+            account_id: receiver_id,
+            sender_id,
+            cost: &U128(cost),
+        }
+        .emit();
+    }
+
+    // 注销由合约代付存储费的账户, 注册存储费不退还给用户
+    // 返回 `None` 表示不是代付注册的账户, 交由标准流程处理
+    pub(crate) fn internal_sponsored_unregister(&mut self, force: Option<bool>) -> Option<bool> {
+        let account_id = env::predecessor_account_id();
+        if !self.sponsor.accounts.contains(&account_id) {
+            return None;
+        }
+        assert_one_yocto();
+        let balance = self.tokens.accounts.get(&account_id).unwrap_or(0);
+        require!(
+            balance == 0 || force.unwrap_or(false),
+            "Can't unregister the account with the positive balance without force"
+        );
+        self.internal_before_balance_change(&account_id);
+        self.tokens.accounts.remove(&account_id);
+        self.tokens.total_supply -= balance;
+        self.sponsor.accounts.remove(&account_id);
+
+        // 存储费中只有账户自己预存的可用余额退还给用户
+        let refund = self.internal_storage_refund(&account_id, true);
+        if refund > 0 {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+        if balance > 0 {
+            // 打印标准 log
+            FtBurn {
+                owner_id: &account_id,
+                amount: &U128(balance),
+                memo: Some("sponsored account closed"),
+            }
+            .emit();
+        }
+        self.on_account_closed(account_id, balance);
+        Some(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{serde_json, testing_env, AccountId, Balance, ONE_NEAR, ONE_YOCTO};
+
+    const HOUR: u64 = 60 * 60 * 1_000_000_000;
+
+    fn account(i: u32) -> AccountId {
+        format!("user{}.near", i).parse().unwrap()
+    }
+
+    // 从 log 中读取最近一次代付注册的存储费
+    fn sponsored_cost() -> Balance {
+        let log = get_logs()
+            .into_iter()
+            .find(|log| log.contains(r#""event":"sponsored_registration""#))
+            .unwrap();
+        let event: serde_json::Value =
+            serde_json::from_str(log.trim_start_matches("EVENT_JSON:")).unwrap();
+        event["data"]["cost"].as_str().unwrap().parse().unwrap()
+    }
+
+    // 代付预算 1 NEAR, 每个发送者每小时最多代付 2 次
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.set_registration_sponsor(U128(ONE_NEAR), 2, U64(HOUR));
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    #[test]
+    fn test_transfer_to_unregistered_account() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(100 * ONE_TOKEN));
+        assert!(contract.is_sponsored(alice()));
+        assert_eq!(contract.sponsored_registrations(), U64(1));
+        assert_eq!(
+            contract.registration_sponsor().budget,
+            U128(ONE_NEAR - sponsored_cost())
+        );
+    }
+
+    #[test]
+    fn test_sponsor_usage_charged_to_budget() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(account(1), U128(ONE_TOKEN), None);
+        let first_cost = sponsored_cost();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(account(2), U128(ONE_TOKEN), None);
+        let second_cost = sponsored_cost();
+
+        // Bob 的限流记录只在第一次代付时新建, 其存储费也从预算中扣除
+        assert!(second_cost < first_cost);
+        assert_eq!(
+            contract.registration_sponsor().budget,
+            U128(ONE_NEAR - first_cost - second_cost)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Sponsored registration rate limit exceeded.")]
+    fn test_sponsor_rate_limit() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(account(1), U128(ONE_TOKEN), None);
+        contract.ft_transfer(account(2), U128(ONE_TOKEN), None);
+        contract.ft_transfer(account(3), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_sponsor_rate_limit_reset() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(account(1), U128(ONE_TOKEN), None);
+        contract.ft_transfer(account(2), U128(ONE_TOKEN), None);
+
+        // 下一个周期可以继续触发代付
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(HOUR)
+            .build());
+
+        contract.ft_transfer(account(3), U128(ONE_TOKEN), None);
+
+        assert_eq!(contract.sponsored_registrations(), U64(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account alice.near is not registered")]
+    fn test_sponsor_disabled() {
+        let mut contract = setup();
+
+        contract.set_registration_sponsor(U128(0), 0, U64(0));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_unregister_sponsored_account() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        assert!(contract.storage_unregister(Some(true)));
+        assert!(contract.storage_balance_of(alice()).is_none());
+        assert!(!contract.is_sponsored(alice()));
+        assert_eq!(contract.ft_total_supply(), U128(900 * ONE_TOKEN));
+        assert!(get_logs().last().unwrap().contains(
+            r#""event":"ft_burn","data":[{"owner_id":"alice.near","amount":"100000000000000000000","memo":"sponsored account closed"}]"#
+        ));
+
+        // 不退还注册存储费
+        assert!(get_created_receipts().is_empty());
+    }
+}
@@ -0,0 +1,459 @@
+use crate::events::{Stake, StakingRewardClaim, StakingRewardRateUpdate, Unstake};
+use crate::u256::U256;
+use crate::{Contract, ContractExt, StorageKey};
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, require, AccountId, Balance, PromiseOrValue};
+
+// 质押时 `ft_transfer_call` 使用的 `msg`
+pub const STAKE_MSG: &str = "stake";
+
+// 每个质押的 FT 累计奖励的放大倍数
+const REWARD_PRECISION: u128 = 1_000_000_000_000_000_000_000_000;
+
+// 质押池: 用户通过 `ft_transfer_call` 把 FT 转给合约自己进行质押,
+// 奖励按 `reward_rate` 每纳秒产出, 按质押数量比例分给所有质押者, 领取时 mint
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StakingPool {
+    // 每纳秒产出的奖励
+    reward_rate: Balance,
+    // 每个质押的 FT 累计分到的奖励, 乘以 `REWARD_PRECISION`
+    reward_per_token: [u64; 4],
+    // 上次更新 `reward_per_token` 的时间戳
+    last_update: u64,
+    total_staked: Balance,
+    stakers: LookupMap<AccountId, Staker>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Staker {
+    staked: Balance,
+    // 上次结算时的 `reward_per_token`
+    reward_per_token_paid: [u64; 4],
+    // 已结算但未领取的奖励
+    rewards: Balance,
+}
+
+impl StakingPool {
+    pub fn new() -> Self {
+        Self {
+            reward_rate: 0,
+            reward_per_token: [0; 4],
+            last_update: 0,
+            total_staked: 0,
+            stakers: LookupMap::new(StorageKey::Stakers),
+        }
+    }
+
+    // 计算截至 `timestamp` 每个质押的 FT 累计分到的奖励, 没有质押时不产出奖励
+    fn reward_per_token_at(&self, timestamp: u64) -> U256 {
+        let reward_per_token = U256(self.reward_per_token);
+        if self.total_staked == 0 {
+            return reward_per_token;
+        }
+        let emitted = U256::from(self.reward_rate) * U256::from(timestamp - self.last_update);
+        reward_per_token + emitted * U256::from(REWARD_PRECISION) / U256::from(self.total_staked)
+    }
+
+    fn rewards_of(&self, staker: &Staker, reward_per_token: U256) -> Balance {
+        let earned = (reward_per_token - U256(staker.reward_per_token_paid))
+            * U256::from(staker.staked)
+            / U256::from(REWARD_PRECISION);
+        staker.rewards + earned.as_u128()
+    }
+
+    // 结算到当前时间并返回账户的质押信息, 修改后需要调用者写回
+    fn settle(&mut self, account_id: &AccountId) -> Staker {
+        let timestamp = env::block_timestamp();
+        self.reward_per_token = self.reward_per_token_at(timestamp).0;
+        self.last_update = timestamp;
+
+        let mut staker = self.stakers.get(account_id).unwrap_or_default();
+        staker.rewards = self.rewards_of(&staker, U256(self.reward_per_token));
+        staker.reward_per_token_paid = self.reward_per_token;
+        staker
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // 合约所有者能设置每纳秒产出的奖励, 首次设置时为合约账户注册持有者信息以接收质押的 FT
+    pub fn set_staking_reward_rate(&mut self, reward_rate: U128) {
+        self.assert_owner();
+        self.assert_not_wrapped_near();
+        let timestamp = env::block_timestamp();
+        self.staking.reward_per_token = self.staking.reward_per_token_at(timestamp).0;
+        self.staking.last_update = timestamp;
+        let old_reward_rate = U128(self.staking.reward_rate);
+        self.staking.reward_rate = reward_rate.0;
+
+        let contract_id = env::current_account_id();
+        if !self.tokens.accounts.contains_key(&contract_id) {
+            self.tokens.internal_register_account(&contract_id);
+            self.internal_index_holder(&contract_id);
+        }
+
+        // 打印事件 log
+        StakingRewardRateUpdate {
+            old_reward_rate: &old_reward_rate,
+            new_reward_rate: &reward_rate,
+        }
+        .emit();
+    }
+
+    // 取回质押的 FT, 同时结算奖励. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn unstake(&mut self, amount: U128) {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let contract_id = env::current_account_id();
+        self.assert_transferable(&contract_id, &account_id);
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_settle_dividends(&account_id);
+        let mut staker = self.staking.settle(&account_id);
+        require!(amount.0 > 0, "The amount should be a positive number");
+        require!(staker.staked >= amount.0, "Not enough staked balance.");
+        staker.staked -= amount.0;
+        self.staking.total_staked -= amount.0;
+        self.staking.stakers.insert(&account_id, &staker);
+
+        self.internal_transfer_unchecked(
+            &contract_id,
+            &account_id,
+            amount.0,
+            Some("unstake".to_string()),
+        );
+        self.internal_charge_storage(&account_id, initial_storage_usage);
+
+        // 打印事件 log
+        Unstake {
+            account_id: &account_id,
+            amount: &amount,
+        }
+        .emit();
+    }
+
+    // 领取质押奖励, 奖励通过 mint 发放. 合约账户作为奖励的 minter, 受供应量上限, 合约账户的 mint 额度
+    // 和 epoch mint 上限限制, 超出限制的部分保留到之后领取. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn claim_rewards(&mut self) -> U128 {
+        assert_one_yocto();
+        self.assert_not_wrapped_near();
+        let account_id = env::predecessor_account_id();
+        let contract_id = env::current_account_id();
+
+        let initial_storage_usage = env::storage_usage();
+        let mut staker = self.staking.settle(&account_id);
+        let amount = [
+            self.ft_remaining_mintable(),
+            self.remaining_mint_quota(contract_id.clone()),
+        ]
+        .iter()
+        .flatten()
+        .fold(staker.rewards, |amount, mintable| {
+            std::cmp::min(amount, mintable.0)
+        });
+        require!(amount > 0, "No rewards to claim.");
+        staker.rewards -= amount;
+        self.staking.stakers.insert(&account_id, &staker);
+
+        self.internal_use_mint_quota(&contract_id, amount);
+        self.internal_mint(&account_id, amount, Some("staking reward".to_string()));
+        self.internal_charge_storage(&account_id, initial_storage_usage);
+
+        // 打印事件 log
+        StakingRewardClaim {
+            account_id: &account_id,
+            amount: &U128(amount),
+        }
+        .emit();
+
+        U128(amount)
+    }
+
+    pub fn staking_reward_rate(&self) -> U128 {
+        U128(self.staking.reward_rate)
+    }
+
+    pub fn total_staked(&self) -> U128 {
+        U128(self.staking.total_staked)
+    }
+
+    pub fn staked_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.internal_staked_balance(&account_id))
+    }
+
+    // 查询截至当前区块尚未领取的奖励
+    pub fn pending_rewards(&self, account_id: AccountId) -> U128 {
+        let staker = self.staking.stakers.get(&account_id).unwrap_or_default();
+        let reward_per_token = self.staking.reward_per_token_at(env::block_timestamp());
+        U128(self.staking.rewards_of(&staker, reward_per_token))
+    }
+}
+
+// 合约自己作为接收合约, 用户通过 `ft_transfer_call` 把 FT 转给合约进行质押, `msg` 为 `STAKE_MSG`
+// 其他 `msg` 的转账会被全部退回
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        require!(
+            env::predecessor_account_id() == env::current_account_id(),
+            "Only accepts transfers of this token."
+        );
+        if msg != STAKE_MSG {
+            return PromiseOrValue::Value(amount);
+        }
+
+        // 新增的质押记录由质押者支付, 存储余额不足时全部退回
+        let initial_storage_usage = env::storage_usage();
+        self.internal_settle_dividends(&sender_id);
+        let mut staker = self.staking.settle(&sender_id);
+        staker.staked += amount.0;
+        self.staking.total_staked += amount.0;
+        self.staking.stakers.insert(&sender_id, &staker);
+        self.internal_charge_storage(&sender_id, initial_storage_usage);
+
+        // 打印事件 log
+        Stake {
+            account_id: &sender_id,
+            amount: &amount,
+        }
+        .emit();
+
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn internal_staked_balance(&self, account_id: &AccountId) -> Balance {
+        self.staking
+            .stakers
+            .get(account_id)
+            .map_or(0, |staker| staker.staked)
+    }
+
+    pub(crate) fn internal_total_staked(&self) -> Balance {
+        self.staking.total_staked
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::staking::STAKE_MSG;
+    use crate::test::{alice, bob, contract_id, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, Balance, PromiseOrValue, ONE_NEAR, ONE_YOCTO};
+
+    const SECOND: u64 = 1_000_000_000;
+    const HOUR: u64 = 60 * 60 * SECOND;
+
+    // Bob 和 Alice 各持有 1000 FT, 每秒产出 1 FT 奖励
+    fn setup(max_supply: Option<U128>) -> Contract {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(owner())
+            .build());
+
+        let mut contract = Contract::init(owner(), metadata(), max_supply, None);
+        contract.mint(bob(), U128(1000 * ONE_TOKEN), None);
+        contract.mint(alice(), U128(1000 * ONE_TOKEN), None);
+        contract.set_staking_reward_rate(U128(ONE_TOKEN / SECOND as Balance));
+        deposit_storage(&mut contract, bob());
+        deposit_storage(&mut contract, alice());
+        contract
+    }
+
+    // 模拟 `ft_transfer_call` 转账给合约自己, 再由合约调用自己的 `ft_on_transfer`
+    fn stake(contract: &mut Contract, account_id: AccountId, amount: Balance, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(account_id.clone())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(timestamp)
+            .build());
+
+        contract.ft_transfer_call(contract_id(), U128(amount), None, STAKE_MSG.to_string());
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(contract_id())
+            .block_timestamp(timestamp)
+            .build());
+
+        let refund = contract.ft_on_transfer(account_id, U128(amount), STAKE_MSG.to_string());
+        assert!(matches!(refund, PromiseOrValue::Value(U128(0))));
+    }
+
+    fn call_as(account_id: AccountId, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(account_id)
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    #[test]
+    fn test_stake_and_claim_rewards() {
+        let mut contract = setup(None);
+
+        stake(&mut contract, bob(), 200 * ONE_TOKEN, 0);
+
+        assert_eq!(contract.ft_balance_of(contract_id()), U128(200 * ONE_TOKEN));
+        assert_eq!(contract.staked_balance_of(bob()), U128(200 * ONE_TOKEN));
+
+        // Bob 独享前 10 秒的奖励, 之后 Alice 质押 200 FT, 两人平分之后 20 秒的奖励
+        stake(&mut contract, alice(), 200 * ONE_TOKEN, 10 * SECOND);
+        call_as(bob(), 30 * SECOND);
+
+        assert_eq!(contract.pending_rewards(bob()), U128(20 * ONE_TOKEN));
+        assert_eq!(contract.pending_rewards(alice()), U128(10 * ONE_TOKEN));
+
+        assert_eq!(contract.claim_rewards(), U128(20 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(bob()), U128(820 * ONE_TOKEN));
+        assert_eq!(contract.pending_rewards(bob()), U128(0));
+    }
+
+    #[test]
+    fn test_unstake() {
+        let mut contract = setup(None);
+
+        stake(&mut contract, bob(), 200 * ONE_TOKEN, 0);
+        call_as(bob(), 10 * SECOND);
+        contract.unstake(U128(200 * ONE_TOKEN));
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.total_staked(), U128(0));
+
+        // 取回后不再产出奖励, 已产出的奖励仍可领取
+        call_as(bob(), 20 * SECOND);
+
+        assert_eq!(contract.pending_rewards(bob()), U128(10 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_dividends_of_staked_tokens() {
+        let mut contract = setup(None);
+
+        stake(&mut contract, bob(), 500 * ONE_TOKEN, 0);
+
+        // 质押的 FT 仍按质押者的份额参与分红, 合约账户不分红
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(owner())
+            .attached_deposit(4 * ONE_NEAR)
+            .build());
+
+        contract.distribute();
+
+        assert_eq!(contract.pending_dividends(bob()), U128(2 * ONE_NEAR));
+        assert_eq!(contract.pending_dividends(alice()), U128(2 * ONE_NEAR));
+        assert_eq!(contract.pending_dividends(contract_id()), U128(0));
+
+        // 取回质押不影响已获得的分红
+        call_as(bob(), 0);
+        contract.unstake(U128(500 * ONE_TOKEN));
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .account_balance(1_000_000 * ONE_NEAR)
+            .build());
+
+        contract.claim_dividends();
+
+        assert!(matches!(
+            &get_created_receipts()[0].actions[..],
+            [VmAction::Transfer { deposit }] if *deposit == 2 * ONE_NEAR
+        ));
+    }
+
+    #[test]
+    fn test_claim_rewards_bounded_by_max_supply() {
+        let mut contract = setup(Some(U128(2005 * ONE_TOKEN)));
+
+        stake(&mut contract, bob(), 200 * ONE_TOKEN, 0);
+        call_as(bob(), 10 * SECOND);
+
+        // 只能 mint 到供应量上限, 剩余的奖励保留
+        assert_eq!(contract.claim_rewards(), U128(5 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(2005 * ONE_TOKEN));
+        assert_eq!(contract.pending_rewards(bob()), U128(5 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_claim_rewards_bounded_by_mint_quota() {
+        let mut contract = setup(None);
+
+        // 合约账户作为奖励的 minter, 受其 mint 额度和 epoch mint 上限限制
+        contract.set_mint_quota(contract_id(), U128(6 * ONE_TOKEN), U64(HOUR));
+        contract.set_epoch_mint_cap(Some(U128(4 * ONE_TOKEN)));
+        stake(&mut contract, bob(), 200 * ONE_TOKEN, 0);
+        call_as(bob(), 10 * SECOND);
+
+        assert_eq!(contract.claim_rewards(), U128(4 * ONE_TOKEN));
+        assert_eq!(contract.remaining_mint_quota(contract_id()), Some(U128(0)));
+
+        // 下一个 epoch 只剩 2 FT 的 mint 额度
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(10 * SECOND)
+            .epoch_height(1)
+            .build());
+
+        assert_eq!(contract.claim_rewards(), U128(2 * ONE_TOKEN));
+        assert_eq!(contract.pending_rewards(bob()), U128(4 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_transfer_call_without_stake_msg() {
+        let mut contract = setup(None);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(contract_id())
+            .build());
+
+        let refund = contract.ft_on_transfer(bob(), U128(ONE_TOKEN), String::new());
+
+        assert!(matches!(refund, PromiseOrValue::Value(U128(amount)) if amount == ONE_TOKEN));
+        assert_eq!(contract.staked_balance_of(bob()), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only accepts transfers of this token.")]
+    fn test_stake_from_other_contract() {
+        let mut contract = setup(None);
+
+        call_as(bob(), 0);
+        contract.ft_on_transfer(bob(), U128(ONE_TOKEN), STAKE_MSG.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough staked balance.")]
+    fn test_unstake_over_staked() {
+        let mut contract = setup(None);
+
+        stake(&mut contract, bob(), 200 * ONE_TOKEN, 0);
+        call_as(bob(), 0);
+        contract.unstake(U128(201 * ONE_TOKEN));
+    }
+}
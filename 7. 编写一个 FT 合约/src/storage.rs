@@ -0,0 +1,232 @@
+use crate::Contract;
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, log, require, AccountId, Balance, Promise, StorageUsage};
+
+// 账户在合约中预存的存储费
+// 通过 `storage_deposit` 注册时支付的部分只覆盖注册时写入的存储, 即 FT 余额, 持有者索引和本记录, 注销时原数退还.
+// 超出的部分为可用余额, 用于支付该账户发起的调用新增的存储, 如历史余额记录, 持有者排名, 分红和质押记录,
+// 这些存储不随注册存储费预先收取, 由谁写入就由谁支付
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct AccountStorage {
+    // 注册时支付的存储费
+    registration: Balance,
+    // 尚未使用的存储费
+    available: Balance,
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 测量注册时除 FT 余额之外写入的存储, FT 余额占用的存储已由 `FungibleToken` 测量. 同时测量代付注册额外写入的存储
+    pub(crate) fn internal_measure_registration_storage_usage(&mut self) {
+        let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
+        self.tokens.accounts.insert(&tmp_account_id, &0);
+        let initial_storage_usage = env::storage_usage();
+        self.internal_index_holder(&tmp_account_id);
+        self.account_storages.insert(
+            &tmp_account_id,
+            &AccountStorage {
+                registration: Balance::MAX,
+                available: Balance::MAX,
+            },
+        );
+        self.registration_storage_usage = env::storage_usage() - initial_storage_usage;
+        self.tokens.accounts.remove(&tmp_account_id);
+        self.internal_index_holder(&tmp_account_id);
+        self.account_storages.remove(&tmp_account_id);
+
+        self.internal_measure_sponsor_storage_usage();
+    }
+
+    pub(crate) fn internal_storage_balance_bounds(&self) -> StorageBalanceBounds {
+        let storage_usage = self.tokens.account_storage_usage + self.registration_storage_usage;
+        StorageBalanceBounds {
+            min: U128(Balance::from(storage_usage) * env::storage_byte_cost()),
+            max: None,
+        }
+    }
+
+    // 没有记录的已注册账户 (迁移前注册或由 mint 自动注册) 与标准实现相同, 只有 FT 余额占用的存储费
+    pub(crate) fn internal_storage_balance_of(
+        &self,
+        account_id: &AccountId,
+    ) -> Option<StorageBalance> {
+        if !self.tokens.accounts.contains_key(account_id) {
+            return None;
+        }
+        let storage_balance = match self.account_storages.get(account_id) {
+            Some(storage) => StorageBalance {
+                total: U128(storage.registration + storage.available),
+                available: U128(storage.available),
+            },
+            None => StorageBalance {
+                total: self.tokens.storage_balance_bounds().min,
+                available: U128(0),
+            },
+        };
+        Some(storage_balance)
+    }
+
+    // 未注册的账户先用附加的 NEAR 支付注册存储费, 其余部分计入可用余额, `registration_only` 时退还给调用者
+    pub(crate) fn internal_storage_deposit(
+        &mut self,
+        account_id: &AccountId,
+        registration_only: bool,
+    ) {
+        let mut amount = env::attached_deposit();
+        if !self.tokens.accounts.contains_key(account_id) {
+            let min_balance = self.internal_storage_balance_bounds().min.0;
+            require!(
+                amount >= min_balance,
+                "The attached deposit is less than the minimum storage balance"
+            );
+            self.internal_register_with_deposit(account_id, min_balance);
+            amount -= min_balance;
+        } else if registration_only {
+            log!("The account is already registered, refunding the deposit");
+        }
+
+        if registration_only {
+            if amount > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(amount);
+            }
+            return;
+        }
+        if amount == 0 {
+            return;
+        }
+
+        // 没有记录的账户新建记录的存储从附加的 NEAR 中扣除
+        let initial_storage_usage = env::storage_usage();
+        let mut storage = self.account_storages.get(account_id).unwrap_or_default();
+        storage.available += amount;
+        self.account_storages.insert(account_id, &storage);
+        self.internal_charge_storage(account_id, initial_storage_usage);
+    }
+
+    // 注册账户并记录其支付的注册存储费
+    pub(crate) fn internal_register_with_deposit(
+        &mut self,
+        account_id: &AccountId,
+        registration: Balance,
+    ) {
+        self.tokens.internal_register_account(account_id);
+        self.internal_index_holder(account_id);
+        self.account_storages.insert(
+            account_id,
+            &AccountStorage {
+                registration,
+                available: 0,
+            },
+        );
+    }
+
+    pub(crate) fn internal_storage_withdraw(
+        &mut self,
+        account_id: &AccountId,
+        amount: Option<Balance>,
+    ) {
+        let mut storage = match self.account_storages.get(account_id) {
+            Some(storage) => storage,
+            None => {
+                require!(
+                    amount.unwrap_or(0) == 0,
+                    "The amount is greater than the available storage balance"
+                );
+                return;
+            }
+        };
+        let amount = amount.unwrap_or(storage.available);
+        require!(
+            amount <= storage.available,
+            "The amount is greater than the available storage balance"
+        );
+        if amount > 0 {
+            storage.available -= amount;
+            self.account_storages.insert(account_id, &storage);
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+    }
+
+    // 从 `payer_id` 的可用余额中扣除 `initial_storage_usage` 之后新增的存储费, 不足时失败. 释放的存储不退还
+    pub(crate) fn internal_charge_storage(
+        &mut self,
+        payer_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        let shortfall = self.internal_charge_available_storage(payer_id, initial_storage_usage);
+        require!(
+            shortfall == 0,
+            format!(
+                "Not enough storage balance, requires {} more yoctoNEAR. Deposit it with `storage_deposit`.",
+                shortfall
+            )
+        );
+    }
+
+    // 从 `payer_id` 的可用余额中尽量扣除新增的存储费, 返回可用余额不足的部分
+    pub(crate) fn internal_charge_available_storage(
+        &mut self,
+        payer_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) -> Balance {
+        let storage_cost =
+            Balance::from(env::storage_usage().saturating_sub(initial_storage_usage))
+                * env::storage_byte_cost();
+        if storage_cost == 0 {
+            return 0;
+        }
+        let mut storage = match self.account_storages.get(payer_id) {
+            Some(storage) => storage,
+            None => return storage_cost,
+        };
+        let charged = std::cmp::min(storage.available, storage_cost);
+        storage.available -= charged;
+        self.account_storages.insert(payer_id, &storage);
+        storage_cost - charged
+    }
+
+    // 与 `FungibleToken::internal_storage_unregister` 相同, 但只退还账户实际支付的存储费
+    pub(crate) fn internal_storage_unregister(
+        &mut self,
+        force: Option<bool>,
+    ) -> Option<(AccountId, Balance)> {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = match self.tokens.accounts.get(&account_id) {
+            Some(balance) => balance,
+            None => {
+                log!("The account {} is not registered", &account_id);
+                return None;
+            }
+        };
+        require!(
+            balance == 0 || force.unwrap_or(false),
+            "Can't unregister the account with the positive balance without force"
+        );
+        self.tokens.accounts.remove(&account_id);
+        self.tokens.total_supply -= balance;
+        let refund = self.internal_storage_refund(&account_id, false);
+        Promise::new(account_id.clone()).transfer(refund + 1);
+        Some((account_id, balance))
+    }
+
+    // 账户注销时删除其记录并返回应退还的存储费, 合约代付注册的账户只退还可用余额
+    // 没有记录的账户与标准实现相同, 退还 FT 余额占用的存储费
+    pub(crate) fn internal_storage_refund(
+        &mut self,
+        account_id: &AccountId,
+        sponsored: bool,
+    ) -> Balance {
+        match self.account_storages.remove(account_id) {
+            Some(storage) if sponsored => storage.available,
+            Some(storage) => storage.registration + storage.available,
+            None if sponsored => 0,
+            None => self.tokens.storage_balance_bounds().min.0,
+        }
+    }
+}
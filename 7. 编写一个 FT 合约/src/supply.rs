@@ -0,0 +1,186 @@
+use crate::events::MaxSupplyUpdate;
+use crate::{Contract, ContractExt};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, require, Balance};
+
+#[near_bindgen]
+impl Contract {
+    // 查询 FT 供应量上限, `None` 表示没有上限
+    pub fn ft_max_supply(&self) -> Option<U128> {
+        self.max_supply.map(U128)
+    }
+
+    // 查询还能 mint 的 FT 数量, `None` 表示没有上限
+    pub fn ft_remaining_mintable(&self) -> Option<U128> {
+        self.max_supply
+            .map(|max_supply| U128(max_supply.saturating_sub(self.tokens.total_supply)))
+    }
+
+    // 合约所有者能降低 FT 供应量上限, 上限只能降低不能提高, 且不能低于当前总供应量. 没有上限时不能设置上限
+    pub fn lower_max_supply(&mut self, max_supply: U128) {
+        self.assert_owner();
+        require!(self.max_supply.is_some(), "Max supply is not set.");
+        let old_max_supply = self.max_supply.unwrap();
+        require!(
+            max_supply.0 < old_max_supply,
+            "Max supply can only be lowered."
+        );
+        require!(
+            max_supply.0 >= self.tokens.total_supply,
+            "Max supply can not be less than total supply."
+        );
+
+        self.max_supply = Some(max_supply.0);
+
+        // 打印事件 log
+        MaxSupplyUpdate {
+            old_max_supply: Some(&U128(old_max_supply)),
+            new_max_supply: &max_supply,
+        }
+        .emit();
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    // 所有 mint 操作都要经过该检查
+    pub(crate) fn assert_mintable(&self, amount: Balance) {
+        if let Some(max_supply) = self.max_supply {
+            let total_supply = self
+                .tokens
+                .total_supply
+                .checked_add(amount)
+                .unwrap_or_else(|| env::panic_str("Total supply overflow"));
+            require!(total_supply <= max_supply, "Max supply exceeded.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{bob, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_mint_within_max_supply() {
+        let mut contract = Contract::init(owner(), metadata(), Some(U128(1000 * ONE_TOKEN)), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(600 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_max_supply(), Some(U128(1000 * ONE_TOKEN)));
+        assert_eq!(
+            contract.ft_remaining_mintable(),
+            Some(U128(400 * ONE_TOKEN))
+        );
+
+        contract.mint(bob(), U128(400 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_remaining_mintable(), Some(U128(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Max supply exceeded.")]
+    fn test_mint_over_max_supply() {
+        let mut contract = Contract::init(owner(), metadata(), Some(U128(1000 * ONE_TOKEN)), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(600 * ONE_TOKEN), None);
+        contract.mint(bob(), U128(401 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_init_with_initial_supply() {
+        let contract = Contract::init(
+            owner(),
+            metadata(),
+            Some(U128(1000 * ONE_TOKEN)),
+            Some(U128(300 * ONE_TOKEN)),
+        );
+
+        assert_eq!(contract.ft_balance_of(owner()), U128(300 * ONE_TOKEN));
+        assert_eq!(contract.ft_total_supply(), U128(300 * ONE_TOKEN));
+        assert_eq!(
+            contract.ft_remaining_mintable(),
+            Some(U128(700 * ONE_TOKEN))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Max supply exceeded.")]
+    fn test_init_with_initial_supply_over_max_supply() {
+        Contract::init(
+            owner(),
+            metadata(),
+            Some(U128(1000 * ONE_TOKEN)),
+            Some(U128(1001 * ONE_TOKEN)),
+        );
+    }
+
+    #[test]
+    fn test_lower_max_supply() {
+        let mut contract = Contract::init(owner(), metadata(), Some(U128(1000 * ONE_TOKEN)), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(100 * ONE_TOKEN), None);
+        contract.lower_max_supply(U128(500 * ONE_TOKEN));
+        contract.lower_max_supply(U128(100 * ONE_TOKEN));
+
+        assert_eq!(contract.ft_max_supply(), Some(U128(100 * ONE_TOKEN)));
+        assert_eq!(contract.ft_remaining_mintable(), Some(U128(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Max supply can only be lowered.")]
+    fn test_raise_max_supply() {
+        let mut contract = Contract::init(owner(), metadata(), Some(U128(1000 * ONE_TOKEN)), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.lower_max_supply(U128(2000 * ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Max supply is not set.")]
+    fn test_lower_max_supply_without_cap() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        assert_eq!(contract.ft_max_supply(), None);
+        assert_eq!(contract.ft_remaining_mintable(), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.lower_max_supply(U128(1000 * ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Max supply can not be less than total supply.")]
+    fn test_lower_max_supply_below_total_supply() {
+        let mut contract = Contract::init(owner(), metadata(), Some(U128(1000 * ONE_TOKEN)), None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.mint(bob(), U128(100 * ONE_TOKEN), None);
+        contract.lower_max_supply(U128(99 * ONE_TOKEN));
+    }
+}
@@ -0,0 +1,7 @@
+// `construct_uint!` 生成的代码无法通过 clippy 检查
+#![allow(clippy::all)]
+
+uint::construct_uint! {
+    // 256 位无符号整数, 用于累计奖励等中间结果可能超出 u128 的计算
+    pub struct U256(4);
+}
@@ -0,0 +1,182 @@
+use crate::events::ContractUpgrade;
+use crate::migrate::{StateVersion, CURRENT_STATE_VERSION};
+use crate::{Contract, ContractExt};
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8};
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, require, CryptoHash, Gas, Promise, PromiseError,
+};
+
+const GAS_FOR_MIGRATE: Gas = Gas(50_000_000_000_000);
+const GAS_FOR_HEALTH_CHECK: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_RESOLVE_UPGRADE: Gas = Gas(10_000_000_000_000);
+// 部署新代码本身消耗的 gas 与代码大小有关, 额外预留 100 TGas
+const GAS_FOR_UPGRADE: Gas = Gas(100_000_000_000_000
+    + GAS_FOR_MIGRATE.0
+    + GAS_FOR_HEALTH_CHECK.0
+    + GAS_FOR_RESOLVE_UPGRADE.0);
+
+#[near_bindgen]
+impl Contract {
+    // 合约所有者能升级合约代码. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    // 部署代码, 调用 `migrate` 和健康检查 `state_version` 在同一个 batch 中执行, 任意一步失败时部署会被回滚
+    #[payable]
+    pub fn upgrade(&mut self, code: Base64VecU8) -> Promise {
+        assert_one_yocto();
+        self.assert_owner();
+        require!(
+            env::prepaid_gas() >= GAS_FOR_UPGRADE,
+            "More gas is required"
+        );
+        let new_code_hash: CryptoHash = env::sha256(&code.0).try_into().unwrap();
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code.0)
+            .function_call("migrate".to_string(), Vec::new(), 0, GAS_FOR_MIGRATE)
+            .function_call(
+                "state_version".to_string(),
+                Vec::new(),
+                0,
+                GAS_FOR_HEALTH_CHECK,
+            )
+            .then(
+                // 升级成功时回调由新代码执行, 因此新代码也必须提供 `resolve_upgrade`
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_UPGRADE)
+                    .resolve_upgrade(new_code_hash.into()),
+            )
+    }
+
+    // 查询最近一次通过 `upgrade` 部署的代码哈希, 从未升级过时返回 `None`
+    pub fn code_hash(&self) -> Option<Base58CryptoHash> {
+        self.code_hash.map(Base58CryptoHash::from)
+    }
+
+    // `upgrade` 的回调, 健康检查返回当前版本时记录新的代码哈希
+    #[private]
+    pub fn resolve_upgrade(
+        &mut self,
+        new_code_hash: Base58CryptoHash,
+        #[callback_result] health_check: Result<StateVersion, PromiseError>,
+    ) -> bool {
+        let success = health_check == Ok(CURRENT_STATE_VERSION);
+        let old_code_hash = self.code_hash.map(Base58CryptoHash::from);
+        if success {
+            self.code_hash = Some(new_code_hash.into());
+        }
+
+        // 打印事件 log
+        ContractUpgrade {
+            old_code_hash: old_code_hash.as_ref(),
+            new_code_hash: &new_code_hash,
+            success,
+        }
+        .emit();
+
+        success
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::migrate::{StateVersion, CURRENT_STATE_VERSION};
+    use crate::test::{alice, metadata, owner};
+    use crate::Contract;
+    use near_sdk::json_types::{Base58CryptoHash, Base64VecU8};
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{env, testing_env, AccountId, CryptoHash, Gas, PromiseError, ONE_YOCTO};
+
+    const CODE: &[u8] = b"\0asm new code";
+
+    fn contract_id() -> AccountId {
+        "hello_ft.near".parse().unwrap()
+    }
+
+    fn new_code_hash() -> Base58CryptoHash {
+        let code_hash: CryptoHash = env::sha256(CODE).try_into().unwrap();
+        code_hash.into()
+    }
+
+    // 模拟 `upgrade` 的回调, `health_check` 为 batch 最后一步的执行结果
+    fn resolve_upgrade(
+        contract: &mut Contract,
+        health_check: Result<StateVersion, PromiseError>,
+    ) -> bool {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(contract_id())
+            .build());
+
+        contract.resolve_upgrade(new_code_hash(), health_check)
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(contract_id())
+            .predecessor_account_id(owner())
+            .attached_deposit(ONE_YOCTO)
+            .prepaid_gas(Gas(300_000_000_000_000))
+            .build());
+
+        contract.upgrade(Base64VecU8(CODE.to_vec()));
+
+        // 部署, 迁移和健康检查在同一个 receipt 中
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, contract_id());
+        assert!(matches!(
+            &receipts[0].actions[..],
+            [
+                VmAction::DeployContract { code },
+                VmAction::FunctionCall { function_name: migrate, .. },
+                VmAction::FunctionCall { function_name: health_check, .. },
+            ] if code == CODE && migrate == "migrate" && health_check == "state_version"
+        ));
+
+        assert!(resolve_upgrade(&mut contract, Ok(CURRENT_STATE_VERSION)));
+
+        assert_eq!(contract.code_hash(), Some(new_code_hash()));
+        assert!(get_logs()[0].contains(r#""event":"contract_upgrade""#));
+        assert!(get_logs()[0].contains(r#""success":true"#));
+    }
+
+    #[test]
+    fn test_upgrade_failed() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        assert!(!resolve_upgrade(&mut contract, Err(PromiseError::Failed)));
+
+        assert_eq!(contract.code_hash(), None);
+        assert!(get_logs()[0].contains(r#""success":false"#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only contract owner can call this method.")]
+    fn test_upgrade_by_other_account() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .prepaid_gas(Gas(300_000_000_000_000))
+            .build());
+
+        contract.upgrade(Base64VecU8(CODE.to_vec()));
+    }
+
+    #[test]
+    #[should_panic(expected = "More gas is required")]
+    fn test_upgrade_without_enough_gas() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(ONE_YOCTO)
+            .prepaid_gas(Gas(100_000_000_000_000))
+            .build());
+
+        contract.upgrade(Base64VecU8(CODE.to_vec()));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::events::{VestingCreate, VestingRevoke};
+use crate::roles::Role;
+use crate::{Contract, ContractExt};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, require, AccountId, Balance};
+
+// 线性释放计划, 时间均为区块时间戳 (纳秒)
+// `cliff` 之前全部锁定, `cliff` 之后按 `start` 到 `end` 线性释放, `end` 之后全部释放
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingSchedule {
+    pub total: U128,
+    pub start: U64,
+    pub cliff: U64,
+    pub end: U64,
+}
+
+impl VestingSchedule {
+    // 查询截至 `timestamp` 已释放的数量
+    pub fn vested_amount(&self, timestamp: u64) -> Balance {
+        let total = self.total.0;
+        if timestamp < self.cliff.0 {
+            return 0;
+        }
+        if timestamp >= self.end.0 {
+            return total;
+        }
+
+        // 拆分计算 total * elapsed / duration, 避免 u128 溢出
+        let elapsed = Balance::from(timestamp - self.start.0);
+        let duration = Balance::from(self.end.0 - self.start.0);
+        total / duration * elapsed + total % duration * elapsed / duration
+    }
+
+    pub fn locked_amount(&self, timestamp: u64) -> Balance {
+        self.total.0 - self.vested_amount(timestamp)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // minter 能为用户 mint 按计划线性释放的 FT, 每个账户同时只能有一个未全部释放的计划
+    pub fn mint_vested(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        start: U64,
+        cliff: U64,
+        end: U64,
+    ) {
+        self.assert_role(Role::Minter);
+        self.assert_not_wrapped_near();
+        require!(amount.0 > 0, "The amount should be a positive number");
+        require!(
+            start.0 <= cliff.0 && cliff.0 <= end.0 && start.0 < end.0,
+            "Vesting schedule must satisfy start <= cliff <= end and start < end."
+        );
+        self.internal_remove_vested_schedule(&account_id);
+        require!(
+            !self.vestings.contains_key(&account_id),
+            "The account already has a vesting schedule."
+        );
+
+        self.internal_use_mint_quota(&env::predecessor_account_id(), amount.0);
+
+        let schedule = VestingSchedule {
+            total: amount,
+            start,
+            cliff,
+            end,
+        };
+        self.vestings.insert(&account_id, &schedule);
+        self.internal_mint(&account_id, amount.0, Some("vesting".to_string()));
+
+        // 打印事件 log
+        VestingCreate {
+            account_id: &account_id,
+            schedule: &schedule,
+        }
+        .emit();
+    }
+
+    // 合约所有者能撤销释放计划, 尚未释放的 FT 会被转回合约所有者 (即项目金库)
+    pub fn revoke_vesting(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let schedule = self.vestings.remove(&account_id);
+        require!(
+            schedule.is_some(),
+            "The account does not have a vesting schedule."
+        );
+
+        // 账户余额可能因被 burn 而小于锁定数量
+        let locked_amount = schedule.unwrap().locked_amount(env::block_timestamp());
+        let balance = self.tokens.accounts.get(&account_id).unwrap_or(0);
+        let amount = std::cmp::min(locked_amount, balance);
+
+        let treasury_id = env::predecessor_account_id();
+        if amount > 0 {
+            if !self.tokens.accounts.contains_key(&treasury_id) {
+                self.tokens.internal_register_account(&treasury_id);
+            }
+            self.internal_transfer_unchecked(
+                &account_id,
+                &treasury_id,
+                amount,
+                Some("vesting revoked".to_string()),
+            );
+        }
+
+        // 打印事件 log
+        VestingRevoke {
+            account_id: &account_id,
+            treasury_id: &treasury_id,
+            amount: &U128(amount),
+        }
+        .emit();
+    }
+
+    pub fn ft_vesting_of(&self, account_id: AccountId) -> Option<VestingSchedule> {
+        self.vestings.get(&account_id)
+    }
+
+    // 查询账户可自由使用的余额, 即余额中已释放的部分
+    pub fn ft_vested_balance_of(&self, account_id: AccountId) -> U128 {
+        let balance = self.tokens.accounts.get(&account_id).unwrap_or(0);
+        U128(balance.saturating_sub(self.internal_locked_balance_of(&account_id)))
+    }
+
+    // 查询账户尚未释放的数量
+    pub fn ft_locked_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.internal_locked_balance_of(&account_id))
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn internal_locked_balance_of(&self, account_id: &AccountId) -> Balance {
+        self.vestings
+            .get(account_id)
+            .map(|schedule| schedule.locked_amount(env::block_timestamp()))
+            .unwrap_or(0)
+    }
+
+    // 释放计划全部释放后删除, 释放存储
+    pub(crate) fn internal_remove_vested_schedule(&mut self, account_id: &AccountId) {
+        let fully_vested = self
+            .vestings
+            .get(account_id)
+            .filter(|schedule| schedule.locked_amount(env::block_timestamp()) == 0)
+            .is_some();
+        if fully_vested {
+            self.vestings.remove(account_id);
+        }
+    }
+
+    // 用户转出或 burn FT 时, 只能使用已释放的部分
+    pub(crate) fn assert_unlocked(&self, account_id: &AccountId, amount: Balance) {
+        let locked_balance = self.internal_locked_balance_of(account_id);
+        if locked_balance > 0 {
+            let balance = self.tokens.accounts.get(account_id).unwrap_or(0);
+            require!(
+                balance.saturating_sub(locked_balance) >= amount,
+                "Insufficient unlocked balance."
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{alice, bob, deposit_storage, metadata, owner, ONE_TOKEN};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, ONE_YOCTO};
+
+    const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    // 给 Bob mint 1000 FT, 第 0 天开始释放, 第 100 天为 cliff, 第 400 天全部释放
+    fn setup() -> Contract {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .attached_deposit(contract.storage_balance_bounds().min.0)
+            .build());
+
+        contract.storage_deposit(Some(alice()), None);
+        contract.mint_vested(
+            bob(),
+            U128(1000 * ONE_TOKEN),
+            U64(0),
+            U64(100 * DAY),
+            U64(400 * DAY),
+        );
+        deposit_storage(&mut contract, bob());
+        contract
+    }
+
+    #[test]
+    fn test_vesting_balances() {
+        let contract = setup();
+
+        testing_env!(VMContextBuilder::new().block_timestamp(50 * DAY).build());
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_locked_balance_of(bob()), U128(1000 * ONE_TOKEN));
+        assert_eq!(contract.ft_vested_balance_of(bob()), U128(0));
+
+        testing_env!(VMContextBuilder::new().block_timestamp(100 * DAY).build());
+
+        assert_eq!(contract.ft_locked_balance_of(bob()), U128(750 * ONE_TOKEN));
+        assert_eq!(contract.ft_vested_balance_of(bob()), U128(250 * ONE_TOKEN));
+
+        testing_env!(VMContextBuilder::new().block_timestamp(500 * DAY).build());
+
+        assert_eq!(contract.ft_locked_balance_of(bob()), U128(0));
+        assert_eq!(contract.ft_vested_balance_of(bob()), U128(1000 * ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_transfer_vested_part() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(200 * DAY)
+            .build());
+
+        contract.ft_transfer(alice(), U128(500 * ONE_TOKEN), None);
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(500 * ONE_TOKEN));
+        assert_eq!(contract.ft_vested_balance_of(bob()), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient unlocked balance.")]
+    fn test_transfer_locked_part() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(200 * DAY)
+            .build());
+
+        contract.ft_transfer(alice(), U128(501 * ONE_TOKEN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient unlocked balance.")]
+    fn test_burn_before_cliff() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(99 * DAY)
+            .build());
+
+        contract.ft_burn(U128(ONE_TOKEN), None);
+    }
+
+    #[test]
+    fn test_revoke_vesting() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(200 * DAY)
+            .build());
+
+        contract.revoke_vesting(bob());
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(500 * ONE_TOKEN));
+        assert_eq!(contract.ft_balance_of(owner()), U128(500 * ONE_TOKEN));
+        assert_eq!(contract.ft_locked_balance_of(bob()), U128(0));
+        assert!(contract.ft_vesting_of(bob()).is_none());
+        assert_eq!(contract.ft_total_supply(), U128(1000 * ONE_TOKEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account already has a vesting schedule.")]
+    fn test_mint_vested_twice() {
+        let mut contract = setup();
+
+        contract.mint_vested(bob(), U128(ONE_TOKEN), U64(0), U64(0), U64(DAY));
+    }
+
+    #[test]
+    fn test_remove_fully_vested_schedule() {
+        let mut contract = setup();
+
+        // 全部释放后, 下一次余额变化时删除释放计划
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .block_timestamp(400 * DAY)
+            .build());
+
+        assert!(contract.ft_vesting_of(bob()).is_some());
+
+        contract.ft_transfer(alice(), U128(100 * ONE_TOKEN), None);
+
+        assert!(contract.ft_vesting_of(bob()).is_none());
+        assert_eq!(contract.ft_vested_balance_of(bob()), U128(900 * ONE_TOKEN));
+
+        // 之后可以为其创建新的释放计划
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(400 * DAY)
+            .build());
+
+        contract.mint_vested(
+            bob(),
+            U128(ONE_TOKEN),
+            U64(400 * DAY),
+            U64(400 * DAY),
+            U64(401 * DAY),
+        );
+
+        assert_eq!(contract.ft_locked_balance_of(bob()), U128(ONE_TOKEN));
+    }
+
+    #[test]
+    fn test_mint_vested_after_fully_vested() {
+        let mut contract = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(400 * DAY)
+            .build());
+
+        contract.mint_vested(
+            bob(),
+            U128(ONE_TOKEN),
+            U64(400 * DAY),
+            U64(400 * DAY),
+            U64(401 * DAY),
+        );
+
+        assert_eq!(contract.ft_balance_of(bob()), U128(1001 * ONE_TOKEN));
+        assert_eq!(contract.ft_locked_balance_of(bob()), U128(ONE_TOKEN));
+    }
+}
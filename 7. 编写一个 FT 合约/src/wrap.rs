@@ -0,0 +1,364 @@
+use crate::events::RoleRevoke;
+use crate::roles::Role;
+use crate::{Contract, ContractExt};
+use near_contract_standards::fungible_token::events::FtMint;
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_contract_standards::storage_management::StorageManagement;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, require, AccountId, Promise};
+
+// wNEAR 模式: 用户存入 NEAR 按 1:1 mint FT, burn FT 取回 NEAR
+#[near_bindgen]
+impl Contract {
+    // 以 wNEAR 模式初始化合约, 供应量没有上限. 供应量只能通过存入和取回 NEAR 改变,
+    // 因此合约所有者不拥有 minter 和 burner 角色
+    #[init]
+    pub fn init_wrapped_near(owner_id: AccountId, metadata: FungibleTokenMetadata) -> Self {
+        require!(
+            metadata.decimals == 24,
+            "Wrapped NEAR must have 24 decimals."
+        );
+        let mut this = Self::init(owner_id.clone(), metadata, None, None);
+        this.wrapped_near = true;
+        for role in [Role::Minter, Role::Burner] {
+            this.internal_revoke_role(&owner_id, role);
+
+            // 打印事件 log
+            RoleRevoke {
+                account_id: &owner_id,
+                role,
+                by: &env::predecessor_account_id(),
+            }
+            .emit();
+        }
+        this
+    }
+
+    pub fn is_wrapped_near(&self) -> bool {
+        self.wrapped_near
+    }
+
+    // 存入 NEAR 并 mint 等量的 FT
+    // 未注册的账户会先从存入的 NEAR 中扣除存储费完成注册, mint 新增的存储优先从可用的存储费余额中支付,
+    // 不足的部分也从存入的 NEAR 中扣除, 剩余部分 mint 为 FT
+    #[payable]
+    pub fn near_deposit(&mut self) {
+        self.assert_wrapped_near();
+        let account_id = env::predecessor_account_id();
+        let mut amount = env::attached_deposit();
+        require!(amount > 0, "Requires positive attached deposit");
+
+        if !self.tokens.accounts.contains_key(&account_id) {
+            self.assert_compliant(&account_id);
+            let storage_cost = self.storage_balance_bounds().min.0;
+            require!(
+                amount >= storage_cost,
+                format!("Must attach {} yoctoNEAR to cover storage.", storage_cost)
+            );
+            self.internal_register_with_deposit(&account_id, storage_cost);
+            amount -= storage_cost;
+        }
+
+        // 附加的 NEAR 恰好等于存储费时只完成注册
+        if amount == 0 {
+            return;
+        }
+        let initial_storage_usage = env::storage_usage();
+        self.internal_mint_without_event(&account_id, amount);
+        let storage_cost =
+            self.internal_charge_available_storage(&account_id, initial_storage_usage);
+        require!(
+            amount > storage_cost,
+            format!(
+                "Must attach more than {} yoctoNEAR to cover storage.",
+                storage_cost
+            )
+        );
+        if storage_cost > 0 {
+            // 同一区块内的历史记录会被覆盖, 扣除时不再新增存储
+            self.internal_before_balance_change(&account_id);
+            self.tokens.internal_withdraw(&account_id, storage_cost);
+            self.internal_on_balance_change(&account_id);
+            self.internal_on_total_supply_change();
+        }
+
+        // 打印标准 log
+        FtMint {
+            owner_id: &account_id,
+            amount: &U128(amount - storage_cost),
+            memo: Some("near_deposit"),
+        }
+        .emit();
+    }
+
+    // burn FT 并取回等量的 NEAR. 调用该方法需要附加 1 yocto NEAR 以保证安全性
+    #[payable]
+    pub fn near_withdraw(&mut self, amount: U128) -> Promise {
+        assert_one_yocto();
+        self.assert_wrapped_near();
+        require!(amount.0 > 0, "The amount should be a positive number");
+        let account_id = env::predecessor_account_id();
+        self.assert_not_frozen(&account_id);
+        self.assert_compliant(&account_id);
+        self.assert_unlocked(&account_id, amount.0);
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_burn(&account_id, amount.0, Some("near_withdraw".to_string()));
+        self.internal_charge_storage(&account_id, initial_storage_usage);
+        Promise::new(account_id).transfer(amount.0)
+    }
+}
+
+// ------------------------------------- 合约内部方法 ------------------------------------------------
+
+impl Contract {
+    pub(crate) fn assert_wrapped_near(&self) {
+        require!(
+            self.wrapped_near,
+            "The contract is not in wrapped NEAR mode."
+        );
+    }
+
+    // wNEAR 模式下 FT 只能通过存入 NEAR 获得
+    pub(crate) fn assert_not_wrapped_near(&self) {
+        require!(
+            !self.wrapped_near,
+            "Minting is disabled in wrapped NEAR mode."
+        );
+    }
+
+    // wNEAR 模式下 burner 不能 burn 用户的 FT, 否则用户存入的 NEAR 无法取回
+    pub(crate) fn assert_burner_allowed(&self) {
+        require!(
+            !self.wrapped_near,
+            "Burning by burner is disabled in wrapped NEAR mode."
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::roles::Role;
+    use crate::test::{alice, bob, metadata, owner};
+    use crate::Contract;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, ONE_NEAR, ONE_YOCTO};
+
+    fn wrapped_metadata() -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            name: "Wrapped NEAR".to_string(),
+            symbol: "wNEAR".to_string(),
+            decimals: 24,
+            ..metadata()
+        }
+    }
+
+    fn setup() -> (Contract, u128) {
+        let contract = Contract::init_wrapped_near(owner(), wrapped_metadata());
+        let storage_cost = contract.storage_balance_bounds().min.0;
+        (contract, storage_cost)
+    }
+
+    #[test]
+    fn test_deposit_withdraw() {
+        let (mut contract, storage_cost) = setup();
+
+        // 未注册的 Alice 存入 10 NEAR, 其中一部分用于支付注册和 mint 新增的存储费
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(10 * ONE_NEAR)
+            .build());
+
+        contract.near_deposit();
+
+        let balance = contract.ft_balance_of(alice()).0;
+        assert!(balance < 10 * ONE_NEAR - storage_cost);
+        assert_eq!(contract.ft_total_supply(), U128(balance));
+
+        // 同一区块内再次存入没有新增存储, 全部 mint 为 FT
+        contract.near_deposit();
+
+        assert_eq!(
+            contract.ft_balance_of(alice()),
+            U128(balance + 10 * ONE_NEAR)
+        );
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.near_withdraw(U128(5 * ONE_NEAR));
+
+        assert_eq!(
+            contract.ft_balance_of(alice()),
+            U128(balance + 5 * ONE_NEAR)
+        );
+        assert_eq!(contract.ft_total_supply(), U128(balance + 5 * ONE_NEAR));
+    }
+
+    #[test]
+    fn test_deposit_with_storage_balance() {
+        let (mut contract, _) = setup();
+
+        // 预存了存储费的账户存入的 NEAR 全部 mint 为 FT
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_NEAR)
+            .build());
+
+        contract.storage_deposit(None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(10 * ONE_NEAR)
+            .build());
+
+        contract.near_deposit();
+
+        assert_eq!(contract.ft_balance_of(alice()), U128(10 * ONE_NEAR));
+        assert!(contract.storage_balance_of(alice()).unwrap().available.0 < ONE_NEAR);
+    }
+
+    #[test]
+    fn test_deposit_exactly_storage_cost() {
+        let (mut contract, storage_cost) = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(storage_cost)
+            .build());
+
+        contract.near_deposit();
+
+        assert!(contract.storage_balance_of(alice()).is_some());
+        assert_eq!(contract.ft_balance_of(alice()), U128(0));
+        assert_eq!(contract.ft_total_supply(), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "yoctoNEAR to cover storage.")]
+    fn test_deposit_less_than_storage_cost() {
+        let (mut contract, storage_cost) = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(storage_cost - 1)
+            .build());
+
+        contract.near_deposit();
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob.near is blocked.")]
+    fn test_withdraw_by_blocked_account() {
+        let (mut contract, _) = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR)
+            .build());
+
+        contract.near_deposit();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.block_account(bob());
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_YOCTO)
+            .build());
+
+        contract.near_withdraw(U128(ONE_YOCTO));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_withdraw_without_one_yocto() {
+        let (mut contract, _) = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR)
+            .build());
+
+        contract.near_deposit();
+        contract.near_withdraw(U128(ONE_YOCTO));
+    }
+
+    #[test]
+    #[should_panic(expected = "Minting is disabled in wrapped NEAR mode.")]
+    fn test_mint_in_wrapped_near_mode() {
+        let (mut contract, _) = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        assert!(!contract.has_role(owner(), Role::Minter));
+
+        contract.grant_role(owner(), Role::Minter);
+        contract.mint(bob(), U128(ONE_NEAR), None);
+    }
+
+    #[test]
+    fn test_init_revokes_supply_roles() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        let contract = Contract::init_wrapped_near(owner(), wrapped_metadata());
+
+        assert!(!contract.has_role(owner(), Role::Minter));
+        assert!(!contract.has_role(owner(), Role::Burner));
+        assert!(contract.has_role(owner(), Role::Pauser));
+
+        let revokes: Vec<_> = get_logs()
+            .into_iter()
+            .filter(|log| log.contains(r#""event":"role_revoke""#))
+            .collect();
+        assert_eq!(revokes.len(), 2);
+        assert!(revokes[0].contains(r#""role":"minter""#));
+        assert!(revokes[1].contains(r#""role":"burner""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Burning by burner is disabled in wrapped NEAR mode.")]
+    fn test_burn_in_wrapped_near_mode() {
+        let (mut contract, storage_cost) = setup();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(storage_cost + ONE_NEAR)
+            .build());
+
+        contract.near_deposit();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+
+        contract.grant_role(owner(), Role::Burner);
+        contract.burn(alice(), U128(ONE_NEAR), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is not in wrapped NEAR mode.")]
+    fn test_deposit_in_normal_mode() {
+        let mut contract = Contract::init(owner(), metadata(), None, None);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(alice())
+            .attached_deposit(ONE_NEAR)
+            .build());
+
+        contract.near_deposit();
+    }
+}